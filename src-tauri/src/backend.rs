@@ -0,0 +1,288 @@
+// @MX:NOTE: Process invocation seam for the download worker. The worker only talks to
+// DownloadBackend/DownloadProcess, so retry, move and status logic can be driven by
+// ScriptedBackend in tests without a real yt-dlp binary.
+
+use crate::file_ops::{configure_hidden_process, managed_path_env, resolve_executable};
+use std::collections::VecDeque;
+use std::fs;
+use std::io::{Cursor, Read};
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::AppHandle;
+
+// ============================================================================
+// Backend traits
+// ============================================================================
+
+/// Everything a backend needs to start one download attempt.
+#[derive(Debug, Clone)]
+pub struct DownloadRequest {
+    pub job_id: String,
+    pub args: Vec<String>,
+    pub temp_dir: PathBuf,
+}
+
+/// A running download attempt started by a `DownloadBackend`.
+pub trait DownloadProcess: Send {
+    /// Takes the process output streams (stdout, stderr). Returns an empty list
+    /// on subsequent calls.
+    fn take_output_streams(&mut self) -> Vec<Box<dyn Read + Send>>;
+
+    /// Returns `Some(success)` once the process has exited, `None` while running.
+    fn try_wait(&mut self) -> std::io::Result<Option<bool>>;
+
+    /// Stops the process, giving it a short grace period before force killing.
+    fn terminate(&mut self);
+}
+
+/// Starts download attempts. The worker owns retry, move and status handling.
+pub trait DownloadBackend: Send + Sync {
+    fn spawn(&self, request: &DownloadRequest) -> Result<Box<dyn DownloadProcess>, String>;
+}
+
+// ============================================================================
+// yt-dlp backend (default)
+// ============================================================================
+
+/// Runs the managed or system yt-dlp executable.
+pub struct YtDlpBackend {
+    app: AppHandle,
+}
+
+impl YtDlpBackend {
+    pub fn new(app: AppHandle) -> Self {
+        Self { app }
+    }
+}
+
+impl DownloadBackend for YtDlpBackend {
+    fn spawn(&self, request: &DownloadRequest) -> Result<Box<dyn DownloadProcess>, String> {
+        let yt_dlp = resolve_executable(&self.app, "yt-dlp");
+        let mut cmd = Command::new(&yt_dlp);
+        configure_hidden_process(&mut cmd);
+        let child = cmd
+            .args(&request.args)
+            .env("PATH", managed_path_env(&self.app))
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|err| err.to_string())?;
+        Ok(Box::new(YtDlpProcess { child }))
+    }
+}
+
+/// A spawned yt-dlp child process.
+pub struct YtDlpProcess {
+    child: Child,
+}
+
+impl DownloadProcess for YtDlpProcess {
+    fn take_output_streams(&mut self) -> Vec<Box<dyn Read + Send>> {
+        let mut streams: Vec<Box<dyn Read + Send>> = Vec::new();
+        if let Some(stdout) = self.child.stdout.take() {
+            streams.push(Box::new(stdout));
+        }
+        if let Some(stderr) = self.child.stderr.take() {
+            streams.push(Box::new(stderr));
+        }
+        streams
+    }
+
+    fn try_wait(&mut self) -> std::io::Result<Option<bool>> {
+        self.child
+            .try_wait()
+            .map(|status| status.map(|exit| exit.success()))
+    }
+
+    fn terminate(&mut self) {
+        terminate_child_with_grace_period(&mut self.child);
+    }
+}
+
+fn try_terminate_child_gracefully(child: &mut Child) -> bool {
+    let pid = child.id().to_string();
+
+    #[cfg(unix)]
+    {
+        let status = Command::new("kill").args(["-INT", &pid]).status();
+        status.map(|result| result.success()).unwrap_or(false)
+    }
+
+    #[cfg(windows)]
+    {
+        let status = Command::new("taskkill").args(["/PID", &pid, "/T"]).status();
+        return status.map(|result| result.success()).unwrap_or(false);
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    {
+        let _ = pid;
+        false
+    }
+}
+
+fn terminate_child_with_grace_period(child: &mut Child) {
+    let graceful_sent = try_terminate_child_gracefully(child);
+
+    if graceful_sent {
+        for _ in 0..10 {
+            match child.try_wait() {
+                Ok(Some(_)) => return,
+                Ok(None) => std::thread::sleep(Duration::from_millis(50)),
+                Err(_) => break,
+            }
+        }
+    }
+
+    match child.try_wait() {
+        Ok(Some(_)) => {
+            // Process already exited; skip force kill.
+        }
+        Ok(None) => {
+            eprintln!("[STABILITY] Process still running after grace period; force killing");
+            #[cfg(windows)]
+            {
+                let pid = child.id().to_string();
+                let _ = Command::new("taskkill")
+                    .args(["/F", "/T", "/PID", &pid])
+                    .status();
+            }
+            let _ = child.kill();
+        }
+        Err(_) => {
+            let _ = child.kill();
+        }
+    }
+}
+
+// ============================================================================
+// Scripted backend (offline testing)
+// ============================================================================
+
+/// One scripted download attempt: the output it prints, how it exits, and the
+/// file it leaves in the job's temp directory.
+#[derive(Debug, Clone, Default)]
+pub struct ScriptedAttempt {
+    pub lines: Vec<String>,
+    pub success: bool,
+    /// `(file name, contents)` written into the temp dir before the process "exits".
+    pub output_file: Option<(String, Vec<u8>)>,
+    /// Keeps running after printing `lines` until terminated, then fails.
+    pub hangs: bool,
+}
+
+impl ScriptedAttempt {
+    /// An attempt that prints `lines`, writes `file_name` and exits successfully.
+    pub fn success(lines: &[&str], file_name: &str) -> Self {
+        Self {
+            lines: lines.iter().map(|line| line.to_string()).collect(),
+            success: true,
+            output_file: Some((file_name.to_string(), b"media".to_vec())),
+            hangs: false,
+        }
+    }
+
+    /// An attempt that prints `lines` and exits with a failure status.
+    pub fn failure(lines: &[&str]) -> Self {
+        Self {
+            lines: lines.iter().map(|line| line.to_string()).collect(),
+            success: false,
+            output_file: None,
+            hangs: false,
+        }
+    }
+
+    /// An attempt that prints `lines`, leaves `partial_file` behind and runs
+    /// until it is terminated.
+    pub fn hanging(lines: &[&str], partial_file: &str) -> Self {
+        Self {
+            lines: lines.iter().map(|line| line.to_string()).collect(),
+            success: false,
+            output_file: Some((partial_file.to_string(), b"partial".to_vec())),
+            hangs: true,
+        }
+    }
+}
+
+/// Replays scripted attempts in order and records every request it receives.
+///
+/// When the script runs out, further attempts fail with a fixed error line.
+#[derive(Default)]
+pub struct ScriptedBackend {
+    attempts: Mutex<VecDeque<ScriptedAttempt>>,
+    requests: Mutex<Vec<DownloadRequest>>,
+}
+
+impl ScriptedBackend {
+    pub fn new(attempts: Vec<ScriptedAttempt>) -> Self {
+        Self {
+            attempts: Mutex::new(attempts.into()),
+            requests: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Returns the requests received so far, oldest first.
+    pub fn requests(&self) -> Vec<DownloadRequest> {
+        self.requests
+            .lock()
+            .map(|requests| requests.clone())
+            .unwrap_or_default()
+    }
+}
+
+impl DownloadBackend for ScriptedBackend {
+    fn spawn(&self, request: &DownloadRequest) -> Result<Box<dyn DownloadProcess>, String> {
+        if let Ok(mut requests) = self.requests.lock() {
+            requests.push(request.clone());
+        }
+        let attempt = self
+            .attempts
+            .lock()
+            .ok()
+            .and_then(|mut attempts| attempts.pop_front())
+            .unwrap_or_else(|| ScriptedAttempt::failure(&["ERROR: script exhausted"]));
+
+        if let Some((file_name, contents)) = &attempt.output_file {
+            fs::create_dir_all(&request.temp_dir).map_err(|err| err.to_string())?;
+            fs::write(request.temp_dir.join(file_name), contents).map_err(|err| err.to_string())?;
+        }
+
+        let mut output = attempt.lines.join("\n");
+        output.push('\n');
+        Ok(Box::new(ScriptedProcess {
+            output: Some(output.into_bytes()),
+            success: attempt.success,
+            running: attempt.hangs,
+        }))
+    }
+}
+
+/// A scripted attempt whose output is replayed from memory.
+pub struct ScriptedProcess {
+    output: Option<Vec<u8>>,
+    success: bool,
+    /// True until `terminate` for hanging attempts.
+    running: bool,
+}
+
+impl DownloadProcess for ScriptedProcess {
+    fn take_output_streams(&mut self) -> Vec<Box<dyn Read + Send>> {
+        match self.output.take() {
+            Some(bytes) => vec![Box::new(Cursor::new(bytes))],
+            None => Vec::new(),
+        }
+    }
+
+    fn try_wait(&mut self) -> std::io::Result<Option<bool>> {
+        if self.running {
+            return Ok(None);
+        }
+        Ok(Some(self.success))
+    }
+
+    fn terminate(&mut self) {
+        self.running = false;
+    }
+}
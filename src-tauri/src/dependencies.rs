@@ -9,6 +9,7 @@ use tauri::{AppHandle, Emitter};
 use crate::file_ops::{
     managed_bin_dir_path, managed_executable_path, resolve_executable, run_command_capture,
 };
+use crate::network::{blocking_client_builder, current_proxy_url};
 
 // ============================================================================
 // Constants
@@ -122,8 +123,9 @@ pub fn installed_ytdlp_version(app: &AppHandle, target: &std::path::Path) -> Opt
 }
 
 /// Queries the GitHub releases API for the latest yt-dlp version tag.
-pub fn latest_ytdlp_version() -> Option<String> {
-    let client = Client::builder()
+pub fn latest_ytdlp_version(proxy_url: Option<&str>) -> Option<String> {
+    let client = blocking_client_builder(proxy_url)
+        .ok()?
         .timeout(Duration::from_millis(YTDLP_VERSION_CHECK_TIMEOUT_MS))
         .build()
         .ok()?;
@@ -214,6 +216,7 @@ pub fn ensure_ytdlp(
 
     let target = managed_executable_path(app, "yt-dlp");
     let _ = fs::create_dir_all(managed_bin_dir_path(app));
+    let proxy_url = current_proxy_url(app);
 
     if target.exists() {
         if !check_latest {
@@ -221,7 +224,7 @@ pub fn ensure_ytdlp(
         }
         let installed = installed_ytdlp_version(app, &target);
         if let Some(installed_version) = installed {
-            if let Some(latest_version) = latest_ytdlp_version() {
+            if let Some(latest_version) = latest_ytdlp_version(proxy_url.as_deref()) {
                 if installed_version == latest_version {
                     return Ok(());
                 }
@@ -241,7 +244,7 @@ pub fn ensure_ytdlp(
     };
 
     set_dependency_status(app, dependency, true, "downloading_yt_dlp", None, None);
-    let client = Client::builder()
+    let client = blocking_client_builder(proxy_url.as_deref())?
         .timeout(Duration::from_secs(180))
         .build()
         .map_err(|err| err.to_string())?;
@@ -320,7 +323,7 @@ pub fn install_ffmpeg_windows(
     let ffprobe_target = managed_executable_path(app, "ffprobe");
     let zip_path = app_data_dir(app).join("ffmpeg-windows.zip");
 
-    let client = Client::builder()
+    let client = blocking_client_builder(current_proxy_url(app).as_deref())?
         .timeout(Duration::from_secs(300))
         .build()
         .map_err(|err| err.to_string())?;
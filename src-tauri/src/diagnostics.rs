@@ -11,6 +11,7 @@ use url::Url;
 use crate::dependencies::{wait_for_dependencies, SharedDependencyState};
 use crate::file_ops::resolve_executable;
 use crate::file_ops::run_command_capture;
+use crate::network::{async_client_builder, current_proxy_url};
 use crate::types::CommandResult;
 
 // ============================================================================
@@ -233,11 +234,14 @@ pub async fn check_update(app: tauri::AppHandle) -> CommandResult<Value> {
 
     let current_version = app.package_info().version.to_string();
 
-    let client = match reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(10))
-        .user_agent(format!("TubeExtract/{}", current_version))
-        .build()
-    {
+    let proxy_url = current_proxy_url(&app);
+    let client = match async_client_builder(proxy_url.as_deref()).and_then(|builder| {
+        builder
+            .timeout(std::time::Duration::from_secs(10))
+            .user_agent(format!("TubeExtract/{}", current_version))
+            .build()
+            .map_err(|err| err.to_string())
+    }) {
         Ok(c) => c,
         Err(e) => {
             eprintln!("[UPDATE] Failed to build HTTP client: {}", e);
@@ -1,22 +1,41 @@
 // @MX:ANCHOR: Central download worker module — all yt-dlp process management lives here.
-// @MX:REASON: start_worker_if_needed, kill_active_child_unchecked, and RuntimeState are
+// @MX:REASON: notify_worker_pool, terminate_job_process, and RuntimeState are
 //             referenced by queue.rs commands and lib.rs run(); high fan_in boundary.
 
+use crate::backend::{DownloadBackend, DownloadProcess, DownloadRequest, YtDlpBackend};
+use crate::diagnostics::calculate_directory_size;
 use crate::file_ops::{
-    configure_hidden_process, managed_path_env, move_file_atomic, remove_directory_safe,
-    resolve_downloaded_file_path, resolve_executable, temp_job_dir_path,
+    available_space, incomplete_marker_path, move_file_with_progress, prepare_staging_dir,
+    remove_directory_safe, resolve_downloaded_file_path, resolve_executable, run_command_capture,
+    temp_job_dir_path, CommandCaptureResult, MoveOptions,
 };
+use crate::hooks::{expand_hook_command, hook_applies, hook_log_lines, PostDownloadHook};
+use crate::integrity::{
+    ffprobe_args, verdict_from_probe_result, IntegrityVerdict, FFPROBE_TIMEOUT_MS,
+};
+use crate::metadata::{fetch_metadata_json, find_fallback_format, FormatSubstitution};
+use crate::network::proxy_args;
+use crate::persistence::{PersistAck, PersistMode};
 use crate::queue::{
-    append_download_log, build_unique_output_path, emit_queue_updated, emit_queue_updated_snapshot,
-    expected_extension, persist_queue, queue_snapshot, select_format_expression,
+    append_download_log, emit_job_updated, emit_queue_updated, expected_extension,
+    job_download_dir, resolve_output_target, select_format_expression, JobUpdate, OutputTarget,
+    QueueItem,
 };
+use crate::settings::{AppSettings, AppState, SponsorBlockMode, TempLocation};
 use crate::state::lock_or_recover;
-use crate::utils::{parse_eta, parse_progress_percent, parse_speed};
+use crate::utils::{
+    format_bytes, parse_eta, parse_progress_percent, parse_speed, parse_sponsorblock_segment_count,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashSet};
 use std::fs;
 use std::io::{BufRead, BufReader};
-use std::process::{Child, Command, Stdio};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver, Sender};
 use std::sync::{Arc, Mutex, TryLockError};
-use std::time::Duration;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tauri::{AppHandle, Manager};
 
 // Delay tables (ms) indexed by retry attempt (clamped to table length).
@@ -24,26 +43,36 @@ const RETRY_DELAY_TABLE_MS: [u64; 4] = [2000, 5000, 10000, 15000];
 const RETRY_DELAY_RATE_LIMIT_MS: [u64; 4] = [30_000, 60_000, 120_000, 120_000];
 const RETRY_DELAY_NETWORK_MS: [u64; 4] = [1_000, 2_000, 5_000, 10_000];
 
+// Bounds applied to user-configured retry policies.
+const DEFAULT_RETRY_JITTER_PERCENT: u32 = 20;
+const MAX_RETRY_JITTER_PERCENT: u32 = 50;
+const MAX_POLICY_RETRIES: u32 = 10;
+const MAX_POLICY_DELAY_MS: u64 = 600_000;
+const MAX_POLICY_DELAY_STEPS: usize = 10;
+
+/// Upper bound on how long app shutdown waits for workers to stop.
+pub const SHUTDOWN_TIMEOUT_MS: u64 = 8_000;
+
 // ============================================================================
 // Runtime state types
 // ============================================================================
 
-/// Holds a reference to a running yt-dlp subprocess for a specific job.
+/// Holds a reference to a running download process for a specific job.
 #[derive(Clone)]
 pub struct ActiveProcess {
     #[allow(dead_code)]
     pub job_id: String,
-    pub child: Arc<Mutex<Child>>,
+    pub child: Arc<Mutex<Box<dyn DownloadProcess>>>,
 }
 
-/// Mutable runtime state tracking active subprocesses and worker thread handles.
+/// Mutable runtime state tracking active subprocesses and the worker pool.
 #[derive(Default)]
 pub struct RuntimeState {
     pub active_processes: std::collections::HashMap<String, ActiveProcess>,
-    // Shutdown senders for graceful worker thread termination.
-    pub shutdown_txs: Vec<std::sync::mpsc::Sender<()>>,
-    // Handles for worker threads.
-    pub worker_handles: Vec<std::thread::JoinHandle<()>>,
+    // Download worker pool; `None` until setup starts it or after shutdown.
+    pub pool: Option<WorkerPool>,
+    /// Set when the app is closing; running jobs stop and are kept resumable.
+    pub shutting_down: bool,
 }
 
 /// Thread-safe shared handle for the download worker runtime state.
@@ -57,28 +86,106 @@ pub struct SharedRuntime(pub Arc<Mutex<RuntimeState>>);
 // @MX:NOTE: Error classification for smart retry strategy (SPEC-STABILITY-005).
 // Pure function: no side effects. Converts raw yt-dlp error strings into a
 // RetryStrategy that determines whether and how quickly to retry a download.
-/// Retry behaviour derived from yt-dlp error output classification.
-#[derive(Debug, PartialEq)]
+/// Retry behaviour derived from yt-dlp error output classification. Also
+/// stored on failed jobs as their error category.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub enum RetryStrategy {
     NoRetry,
+    /// The content is restricted and needs cookies from a signed-in account.
+    NeedsAuth,
+    /// YouTube asks the client to sign in to prove it is not a bot.
+    BotCheck,
+    /// The selected format no longer exists for this video.
+    FormatUnavailable,
+    /// The video is not available in the user's region.
+    GeoBlocked,
+    /// The download or merge ran out of disk space.
+    DiskFull,
+    /// ffmpeg failed while merging or post-processing.
+    FfmpegError,
     RateLimit,
     NetworkError,
     Default,
 }
 
+/// Per-category retry override. Unset fields fall back to the built-in policy.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RetryRule {
+    /// Retries after the first attempt.
+    #[serde(default)]
+    pub max_retries: Option<u32>,
+    /// Delay before each retry, indexed by attempt and clamped to the last entry.
+    #[serde(default)]
+    pub delays_ms: Vec<u64>,
+}
+
+/// User-configurable retry policy.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RetryPolicy {
+    /// Random spread applied to every delay, as a percentage either way.
+    #[serde(default = "default_retry_jitter_percent")]
+    pub jitter_percent: u32,
+    #[serde(default)]
+    pub rules: BTreeMap<RetryStrategy, RetryRule>,
+}
+
+fn default_retry_jitter_percent() -> u32 {
+    DEFAULT_RETRY_JITTER_PERCENT
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            jitter_percent: DEFAULT_RETRY_JITTER_PERCENT,
+            rules: BTreeMap::new(),
+        }
+    }
+}
+
 /// Classifies a yt-dlp error string into a RetryStrategy.
 pub fn classify_download_error(error: &str) -> RetryStrategy {
     let lower = error.to_lowercase();
+    // Restricted content — retrying is pointless until cookies are attached
+    if lower.contains("private video")
+        || lower.contains("this video is private")
+        || lower.contains("age-restricted")
+        || lower.contains("confirm your age")
+        || lower.contains("members-only")
+        || lower.contains("members only")
+        || lower.contains("join this channel")
+    {
+        return RetryStrategy::NeedsAuth;
+    }
+    if lower.contains("not a bot") || lower.contains("sign in to confirm you") {
+        return RetryStrategy::BotCheck;
+    }
+    if lower.contains("no space left on device")
+        || lower.contains("disk full")
+        || lower.contains("not enough space")
+    {
+        return RetryStrategy::DiskFull;
+    }
+    if lower.contains("requested format is not available")
+        || lower.contains("format is not available")
+    {
+        return RetryStrategy::FormatUnavailable;
+    }
+    if lower.contains("available in your country")
+        || lower.contains("geo-restricted")
+        || lower.contains("geo restricted")
+        || lower.contains("blocked it in your country")
+    {
+        return RetryStrategy::GeoBlocked;
+    }
     // Permanent errors — no retry
     if lower.contains("video unavailable")
-        || lower.contains("private video")
         || lower.contains("has been removed")
         || lower.contains("not available")
         || lower.contains("http error 404")
         || lower.contains("http error 403")
-        || lower.contains("age-restricted")
-        || lower.contains("this video is private")
-        || lower.contains("members-only")
     {
         return RetryStrategy::NoRetry;
     }
@@ -98,9 +205,31 @@ pub fn classify_download_error(error: &str) -> RetryStrategy {
     {
         return RetryStrategy::NetworkError;
     }
+    if lower.contains("ffmpeg")
+        || lower.contains("postprocessing")
+        || lower.contains("conversion failed")
+    {
+        return RetryStrategy::FfmpegError;
+    }
     RetryStrategy::Default
 }
 
+/// User-facing prompt shown when a download or analysis needs authentication.
+pub fn needs_auth_message(detail: &str) -> String {
+    format!(
+        "로그인이 필요한 콘텐츠입니다. 설정에서 쿠키 파일 또는 브라우저 프로필을 지정한 뒤 다시 시도해 주세요. ({})",
+        detail.trim()
+    )
+}
+
+/// Pause reason shown when a download fails because the disk filled up.
+pub fn disk_full_message(detail: &str) -> String {
+    format!(
+        "디스크 공간이 부족하여 대기열을 일시 정지했습니다. 공간을 확보한 뒤 대기열을 재개해 주세요. ({})",
+        detail.trim()
+    )
+}
+
 /// Returns the delay in milliseconds before retrying based on strategy and attempt number.
 pub fn retry_delay_ms_for_strategy(strategy: &RetryStrategy, attempt: usize) -> u64 {
     let table = match strategy {
@@ -112,15 +241,371 @@ pub fn retry_delay_ms_for_strategy(strategy: &RetryStrategy, attempt: usize) ->
     table[idx]
 }
 
+/// Clamps a retry policy loaded from settings to sane bounds.
+pub fn normalize_retry_policy(policy: RetryPolicy) -> RetryPolicy {
+    RetryPolicy {
+        jitter_percent: policy.jitter_percent.min(MAX_RETRY_JITTER_PERCENT),
+        rules: policy
+            .rules
+            .into_iter()
+            .map(|(strategy, rule)| {
+                let rule = RetryRule {
+                    max_retries: rule.max_retries.map(|max| max.min(MAX_POLICY_RETRIES)),
+                    delays_ms: rule
+                        .delays_ms
+                        .into_iter()
+                        .take(MAX_POLICY_DELAY_STEPS)
+                        .map(|delay| delay.min(MAX_POLICY_DELAY_MS))
+                        .collect(),
+                };
+                (strategy, rule)
+            })
+            .collect(),
+    }
+}
+
+/// Spreads `delay_ms` by up to `jitter_percent` either way. `sample` is a
+/// random value in `[0, 1)`; 0.5 leaves the delay unchanged.
+pub fn apply_retry_jitter(delay_ms: u64, jitter_percent: u32, sample: f64) -> u64 {
+    let spread = delay_ms as f64 * f64::from(jitter_percent) / 100.0;
+    let offset = spread * (sample.clamp(0.0, 1.0) * 2.0 - 1.0);
+    (delay_ms as f64 + offset).max(0.0).round() as u64
+}
+
+impl RetryPolicy {
+    /// Retries allowed for `strategy`. Without an override, categories that
+    /// cannot succeed on retry get none and the rest use `default_max`.
+    pub fn max_retries(&self, strategy: RetryStrategy, default_max: usize) -> usize {
+        if let Some(max) = self.rules.get(&strategy).and_then(|rule| rule.max_retries) {
+            return max as usize;
+        }
+        match strategy {
+            RetryStrategy::NoRetry
+            | RetryStrategy::NeedsAuth
+            | RetryStrategy::BotCheck
+            | RetryStrategy::FormatUnavailable
+            | RetryStrategy::GeoBlocked
+            | RetryStrategy::DiskFull => 0,
+            _ => default_max,
+        }
+    }
+
+    /// Delay before the retry that follows `attempt` earlier retries, without jitter.
+    pub fn base_delay_ms(&self, strategy: RetryStrategy, attempt: usize) -> u64 {
+        match self.rules.get(&strategy) {
+            Some(rule) if !rule.delays_ms.is_empty() => {
+                rule.delays_ms[attempt.min(rule.delays_ms.len() - 1)]
+            }
+            _ => retry_delay_ms_for_strategy(&strategy, attempt),
+        }
+    }
+
+    /// Delay before the retry that follows `attempt` earlier retries, with jitter applied.
+    pub fn delay_ms(&self, strategy: RetryStrategy, attempt: usize) -> u64 {
+        // Jitter only has to spread retries of parallel jobs apart, so the
+        // clock's sub-second part is random enough without an RNG dependency.
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .subsec_nanos();
+        let sample = f64::from(nanos % 10_000) / 10_000.0;
+        apply_retry_jitter(
+            self.base_delay_ms(strategy, attempt),
+            self.jitter_percent,
+            sample,
+        )
+    }
+}
+
+// ============================================================================
+// Post-processing arguments
+// ============================================================================
+
+/// Resolved embed options for a single job (per-job override or settings default).
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct EmbedOptions {
+    pub metadata: bool,
+    pub thumbnail: bool,
+    pub chapters: bool,
+}
+
+impl EmbedOptions {
+    /// Resolves the embed options for a job, falling back to the settings defaults.
+    pub fn resolve(job: &QueueItem, settings: &AppSettings) -> Self {
+        Self {
+            metadata: job.embed_metadata.unwrap_or(settings.embed_metadata),
+            thumbnail: job.embed_thumbnail.unwrap_or(settings.embed_thumbnail),
+            chapters: job.embed_chapters.unwrap_or(settings.embed_chapters),
+        }
+    }
+}
+
+/// Returns the yt-dlp flags that embed metadata, cover art and chapter markers.
+///
+/// Thumbnails are converted to JPEG first because MP4 and MP3 containers
+/// cannot carry the WebP artwork YouTube serves by default.
+pub fn embed_args(options: &EmbedOptions) -> Vec<String> {
+    let mut args = Vec::new();
+    if options.metadata {
+        args.push("--embed-metadata".to_string());
+    }
+    if options.thumbnail {
+        args.push("--embed-thumbnail".to_string());
+        args.push("--convert-thumbnails".to_string());
+        args.push("jpg".to_string());
+    }
+    if options.chapters {
+        args.push("--embed-chapters".to_string());
+    }
+    args
+}
+
+/// Returns the yt-dlp flags that attach cookies to a request.
+///
+/// A cookies file takes precedence over a browser profile when both are set.
+pub fn cookie_args(cookies_file: Option<&str>, cookies_browser: Option<&str>) -> Vec<String> {
+    if let Some(file) = cookies_file.filter(|value| !value.trim().is_empty()) {
+        return vec!["--cookies".to_string(), file.trim().to_string()];
+    }
+    if let Some(browser) = cookies_browser.filter(|value| !value.trim().is_empty()) {
+        return vec![
+            "--cookies-from-browser".to_string(),
+            browser.trim().to_string(),
+        ];
+    }
+    Vec::new()
+}
+
+/// Resolves the cookie flags for a job, preferring its own cookie source over the settings.
+pub fn job_cookie_args(job: &QueueItem, settings: &AppSettings) -> Vec<String> {
+    if job.cookies_file.is_some() || job.cookies_browser.is_some() {
+        return cookie_args(job.cookies_file.as_deref(), job.cookies_browser.as_deref());
+    }
+    cookie_args(
+        settings.cookies_file.as_deref(),
+        settings.cookies_browser.as_deref(),
+    )
+}
+
+/// Returns the yt-dlp flags that remove or mark the given SponsorBlock categories.
+pub fn sponsorblock_args(mode: SponsorBlockMode, categories: &[String]) -> Vec<String> {
+    if categories.is_empty() {
+        return Vec::new();
+    }
+    let flag = match mode {
+        SponsorBlockMode::Off => return Vec::new(),
+        SponsorBlockMode::Remove => "--sponsorblock-remove",
+        SponsorBlockMode::Mark => "--sponsorblock-mark",
+    };
+    vec![flag.to_string(), categories.join(",")]
+}
+
+// ============================================================================
+// Download arguments
+// ============================================================================
+
+/// Builds the full yt-dlp argument list for one download attempt.
+pub fn build_download_args(
+    job: &QueueItem,
+    settings: &AppSettings,
+    output_template: &Path,
+) -> Vec<String> {
+    let format_expr = select_format_expression(&job.mode, &job.quality_id);
+    let mut args = vec![
+        "--no-playlist".to_string(),
+        "--newline".to_string(),
+        "--progress".to_string(),
+        "--socket-timeout".to_string(),
+        "30".to_string(),
+        "--fragment-retries".to_string(),
+        "10".to_string(),
+        "--throttled-rate".to_string(),
+        "100K".to_string(),
+        "--extractor-retries".to_string(),
+        "5".to_string(),
+        "--concurrent-fragments".to_string(),
+        "4".to_string(),
+        "-f".to_string(),
+        format_expr,
+        "-o".to_string(),
+        output_template.to_string_lossy().to_string(),
+        job.url.clone(),
+    ];
+
+    match job.mode {
+        crate::metadata::DownloadMode::Audio => {
+            args.push("-x".to_string());
+            args.push("--audio-format".to_string());
+            args.push("mp3".to_string());
+        }
+        crate::metadata::DownloadMode::Video => {
+            args.push("--merge-output-format".to_string());
+            args.push("mp4".to_string());
+            args.push("--recode-video".to_string());
+            args.push("mp4".to_string());
+        }
+    }
+
+    let sponsorblock_mode = job.sponsorblock_mode.unwrap_or(settings.sponsorblock_mode);
+    args.extend(embed_args(&EmbedOptions::resolve(job, settings)));
+    args.extend(sponsorblock_args(
+        sponsorblock_mode,
+        &settings.sponsorblock_categories,
+    ));
+    args.extend(job_cookie_args(job, settings));
+    args.extend(proxy_args(settings.proxy_url.as_deref()));
+    args
+}
+
+// ============================================================================
+// Disk space preflight
+// ============================================================================
+
+/// Checks each `(path, available bytes)` volume against the job's estimated
+/// size plus `reserve_bytes`. Returns a user-facing reason for the first volume
+/// that falls short; volumes with unknown free space are skipped.
+pub fn disk_space_shortfall(
+    estimated_bytes: Option<u64>,
+    reserve_bytes: u64,
+    volumes: &[(&Path, Option<u64>)],
+) -> Option<String> {
+    let required = estimated_bytes.unwrap_or(0).saturating_add(reserve_bytes);
+    volumes.iter().find_map(|(path, available)| {
+        let available = (*available)?;
+        if available >= required {
+            return None;
+        }
+        Some(format!(
+            "디스크 공간이 부족하여 대기열을 일시 중지했습니다. {} (사용 가능: {}, 필요: {})",
+            path.display(),
+            format_bytes(available),
+            format_bytes(required)
+        ))
+    })
+}
+
+/// Returns the job to the queue and stops dispatching until the user resumes.
+fn pause_queue_for_low_space(
+    host: &dyn WorkerHost,
+    shared: &Arc<Mutex<AppState>>,
+    job_id: &str,
+    reason: String,
+) {
+    eprintln!("[STABILITY] {reason}");
+    let mut state = lock_or_recover(shared, "worker_thread/low_space_pause");
+    if let Some(item) = state.queue.iter_mut().find(|item| item.id == job_id) {
+        if item.status == "downloading" {
+            item.status = "queued".to_string();
+        }
+        item.progress_percent = 0.0;
+        item.error_message = Some(reason.clone());
+        append_download_log(item, &reason);
+    }
+    state.queue_pause_reason = Some(reason);
+    host.emit_queue(&state);
+    let saved = host.persist_queue(&state, PersistMode::Immediate);
+    drop(state);
+    saved.wait();
+}
+
+// ============================================================================
+// Worker host
+// ============================================================================
+
+/// Application side effects the worker depends on. The Tauri implementation
+/// emits events and writes to app data; tests substitute an in-memory host.
+pub trait WorkerHost: Send + Sync {
+    /// Publishes changed fields of a single job to the frontend.
+    fn emit_job(&self, update: JobUpdate);
+    /// Publishes a full queue snapshot for queue-level changes.
+    fn emit_queue(&self, state: &AppState);
+    /// Persists the queue to disk; see `queue::persist_queue` for the ack.
+    fn persist_queue(&self, state: &AppState, mode: PersistMode) -> PersistAck;
+    /// Returns the temp directory that holds a job's in-progress files.
+    fn temp_job_dir(&self, job_id: &str) -> PathBuf;
+    /// Blocks until yt-dlp and ffmpeg are ready.
+    fn wait_for_dependencies(&self) -> Result<(), String>;
+    /// Free bytes on the volume holding `path`, or `None` when unknown.
+    fn available_space(&self, path: &Path) -> Option<u64>;
+    /// Runs a command to completion (killed after `timeout_ms`) and captures its output.
+    fn run_command(&self, program: &str, args: &[String], timeout_ms: u64) -> CommandCaptureResult;
+    /// Runs ffprobe on `path`, or returns `None` when ffprobe is not installed.
+    fn probe_media(&self, path: &Path) -> Option<CommandCaptureResult>;
+    /// Fetches the current yt-dlp metadata (including `formats`) for `url`.
+    fn fetch_formats(&self, url: &str, extra_args: &[String]) -> Result<Value, String>;
+}
+
+/// `WorkerHost` backed by the running Tauri application.
+pub struct TauriWorkerHost {
+    app: AppHandle,
+}
+
+impl TauriWorkerHost {
+    pub fn new(app: AppHandle) -> Self {
+        Self { app }
+    }
+}
+
+impl WorkerHost for TauriWorkerHost {
+    fn emit_job(&self, update: JobUpdate) {
+        emit_job_updated(&self.app, update);
+    }
+
+    fn emit_queue(&self, state: &AppState) {
+        emit_queue_updated(&self.app, state);
+    }
+
+    fn persist_queue(&self, state: &AppState, mode: PersistMode) -> PersistAck {
+        crate::queue::persist_queue(&self.app, state, mode)
+    }
+
+    fn temp_job_dir(&self, job_id: &str) -> PathBuf {
+        temp_job_dir_path(&self.app, job_id)
+    }
+
+    fn wait_for_dependencies(&self) -> Result<(), String> {
+        match self
+            .app
+            .try_state::<crate::dependencies::SharedDependencyState>()
+        {
+            Some(dependency) => {
+                crate::dependencies::wait_for_dependencies(&self.app, &dependency.0)
+            }
+            None => Ok(()),
+        }
+    }
+
+    fn available_space(&self, path: &Path) -> Option<u64> {
+        available_space(path)
+    }
+
+    fn run_command(&self, program: &str, args: &[String], timeout_ms: u64) -> CommandCaptureResult {
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        run_command_capture(&self.app, program, &args, timeout_ms)
+    }
+
+    fn probe_media(&self, path: &Path) -> Option<CommandCaptureResult> {
+        let ffprobe = resolve_executable(&self.app, "ffprobe");
+        if !Path::new(&ffprobe).exists() {
+            return None;
+        }
+        Some(self.run_command(&ffprobe, &ffprobe_args(path), FFPROBE_TIMEOUT_MS))
+    }
+
+    fn fetch_formats(&self, url: &str, extra_args: &[String]) -> Result<Value, String> {
+        fetch_metadata_json(&self.app, url, extra_args)
+    }
+}
+
 // ============================================================================
 // Worker process management
 // ============================================================================
 
-/// Kills all active yt-dlp subprocesses without waiting for graceful shutdown.
+/// Kills all active download processes, giving each a short grace period.
 // @MX:ANCHOR: [AUTO] Critical cleanup function for terminating download workers.
-// @MX:REASON: [AUTO] High fan_in: pause_job, cancel_job, and clear_terminal_jobs spawn threads that call this.
+// @MX:REASON: [AUTO] Called on app shutdown once the pool stops dispatching.
 pub fn kill_active_child_unchecked(runtime: &Arc<Mutex<RuntimeState>>) {
-    let children: Vec<Arc<Mutex<Child>>> = runtime
+    let children: Vec<Arc<Mutex<Box<dyn DownloadProcess>>>> = runtime
         .lock()
         .ok()
         .map(|mut guard| {
@@ -133,81 +618,183 @@ pub fn kill_active_child_unchecked(runtime: &Arc<Mutex<RuntimeState>>) {
         .unwrap_or_default();
     for child in children {
         if let Ok(mut locked_child) = child.lock() {
-            terminate_child_with_grace_period(&mut locked_child);
+            locked_child.terminate();
         }
     }
 }
 
-fn try_terminate_child_gracefully(child: &mut Child) -> bool {
-    let pid = child.id().to_string();
-
-    #[cfg(unix)]
-    {
-        let status = Command::new("kill").args(["-INT", &pid]).status();
-        status.map(|result| result.success()).unwrap_or(false)
+/// Terminates the download process of `job_id` only; other workers keep running.
+pub fn terminate_job_process(runtime: &Arc<Mutex<RuntimeState>>, job_id: &str) {
+    let child = runtime
+        .lock()
+        .ok()
+        .and_then(|mut guard| guard.active_processes.remove(job_id))
+        .map(|active| active.child);
+    if let Some(child) = child {
+        if let Ok(mut locked_child) = child.lock() {
+            locked_child.terminate();
+        }
     }
+}
 
-    #[cfg(windows)]
-    {
-        let status = Command::new("taskkill").args(["/PID", &pid, "/T"]).status();
-        return status.map(|result| result.success()).unwrap_or(false);
+fn clear_active_process(runtime: &Arc<Mutex<RuntimeState>>, job_id: &str) {
+    if let Ok(mut guard) = runtime.lock() {
+        guard.active_processes.remove(job_id);
     }
+}
 
-    #[cfg(not(any(unix, windows)))]
-    {
-        let _ = pid;
-        false
-    }
+// ============================================================================
+// Job update throttling
+// ============================================================================
+
+/// Minimum interval between output-driven `job-updated` events for one job.
+const JOB_UPDATE_INTERVAL_MS: u64 = 250;
+
+#[derive(Default)]
+struct ThrottleState {
+    last_emitted: Option<Instant>,
+    pending_log: Vec<String>,
+    dirty: bool,
 }
 
-fn terminate_child_with_grace_period(child: &mut Child) {
-    let graceful_sent = try_terminate_child_gracefully(child);
+/// Coalesces per-job updates so a chatty yt-dlp process produces at most one
+/// `job-updated` event per interval. Log lines held back in the meantime are
+/// delivered with the next event.
+///
+/// Lock order is always `AppState` first, then the throttle.
+struct JobUpdateThrottle {
+    job_id: String,
+    interval: Duration,
+    state: Mutex<ThrottleState>,
+}
 
-    if graceful_sent {
-        for _ in 0..10 {
-            match child.try_wait() {
-                Ok(Some(_)) => return,
-                Ok(None) => std::thread::sleep(Duration::from_millis(50)),
-                Err(_) => break,
-            }
+impl JobUpdateThrottle {
+    fn new(job_id: &str) -> Self {
+        Self {
+            job_id: job_id.to_string(),
+            interval: Duration::from_millis(JOB_UPDATE_INTERVAL_MS),
+            state: Mutex::new(ThrottleState::default()),
         }
     }
 
-    match child.try_wait() {
-        Ok(Some(_)) => {
-            // Process already exited; skip force kill.
+    /// Records a change to `item`. Returns the update to emit when the interval
+    /// has elapsed or `force` is set; otherwise the change stays pending.
+    fn record(&self, item: &QueueItem, log_lines: Vec<String>, force: bool) -> Option<JobUpdate> {
+        let mut throttle = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        throttle.pending_log.extend(log_lines);
+        throttle.dirty = true;
+        let due = throttle
+            .last_emitted
+            .map(|last| last.elapsed() >= self.interval)
+            .unwrap_or(true);
+        if !force && !due {
+            return None;
         }
-        Ok(None) => {
-            eprintln!("[STABILITY] Process still running after grace period; force killing");
-            #[cfg(windows)]
-            {
-                let pid = child.id().to_string();
-                let _ = Command::new("taskkill")
-                    .args(["/F", "/T", "/PID", &pid])
-                    .status();
+        throttle.dirty = false;
+        throttle.last_emitted = Some(Instant::now());
+        let log_lines = std::mem::take(&mut throttle.pending_log);
+        Some(JobUpdate::from_item(item, log_lines))
+    }
+
+    /// Emits a held-back change once its interval has passed.
+    fn flush_due(&self, shared: &Arc<Mutex<AppState>>, host: &dyn WorkerHost) {
+        let update = {
+            let state = lock_or_recover(shared, "job_update_throttle/flush");
+            let dirty = self
+                .state
+                .lock()
+                .map(|throttle| throttle.dirty)
+                .unwrap_or(false);
+            if !dirty {
+                return;
             }
-            let _ = child.kill();
-        }
-        Err(_) => {
-            let _ = child.kill();
+            state
+                .queue
+                .iter()
+                .find(|item| item.id == self.job_id)
+                .and_then(|item| self.record(item, Vec::new(), false))
+        };
+        if let Some(update) = update {
+            host.emit_job(update);
         }
     }
 }
 
-fn clear_active_process(runtime: &Arc<Mutex<RuntimeState>>, job_id: &str) {
-    if let Ok(mut guard) = runtime.lock() {
-        guard.active_processes.remove(job_id);
+// ============================================================================
+// Stall watchdog
+// ============================================================================
+
+/// How often the watchdog measures the temp dir for byte progress.
+const STALL_BYTES_CHECK_INTERVAL_MS: u64 = 2_000;
+
+struct WatchdogState {
+    last_activity: Instant,
+    last_line: Option<String>,
+    last_bytes: u64,
+}
+
+/// Tracks the last sign of life of one download attempt: a new output line
+/// or growth of the files in its temp dir. The byte check keeps long ffmpeg
+/// merges, which print nothing, from being mistaken for stalls.
+pub struct StallWatchdog {
+    timeout: Option<Duration>,
+    state: Mutex<WatchdogState>,
+}
+
+impl StallWatchdog {
+    /// Starts watching at `now`. A `timeout_sec` of 0 disables detection.
+    pub fn new(timeout_sec: u64, now: Instant) -> Self {
+        Self {
+            timeout: (timeout_sec > 0).then(|| Duration::from_secs(timeout_sec)),
+            state: Mutex::new(WatchdogState {
+                last_activity: now,
+                last_line: None,
+                last_bytes: 0,
+            }),
+        }
+    }
+
+    /// Counts an output line as activity unless it repeats the previous one.
+    pub fn observe_line(&self, line: &str, now: Instant) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if state.last_line.as_deref() != Some(line) {
+            state.last_line = Some(line.to_string());
+            state.last_activity = now;
+        }
+    }
+
+    /// Counts growth of the bytes written so far as activity.
+    pub fn observe_bytes(&self, bytes: u64, now: Instant) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if bytes > state.last_bytes {
+            state.last_bytes = bytes;
+            state.last_activity = now;
+        }
+    }
+
+    /// Returns true once nothing has happened for longer than the timeout.
+    pub fn is_stalled(&self, now: Instant) -> bool {
+        let Some(timeout) = self.timeout else {
+            return false;
+        };
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        now.saturating_duration_since(state.last_activity) > timeout
     }
 }
 
+/// Error recorded on a job whose download was restarted by the watchdog.
+pub fn stalled_message(timeout_sec: u64) -> String {
+    format!("다운로드가 {timeout_sec}초 동안 진행되지 않아 중단했습니다.")
+}
+
 // ============================================================================
 // Download output line handler
 // ============================================================================
 
 fn handle_download_output_line(
-    shared: &Arc<Mutex<crate::settings::AppState>>,
-    app: &AppHandle,
-    job_id: &str,
+    shared: &Arc<Mutex<AppState>>,
+    host: &dyn WorkerHost,
+    throttle: &JobUpdateThrottle,
     line: &str,
 ) {
     let normalized = line.trim();
@@ -215,8 +802,9 @@ fn handle_download_output_line(
         return;
     }
 
-    let snapshot_to_emit = {
+    let update_to_emit = {
         let mut should_emit = false;
+        let mut appended: Vec<String> = Vec::new();
         let mut state = match shared.try_lock() {
             Ok(guard) => guard,
             Err(TryLockError::WouldBlock) => return,
@@ -226,361 +814,1108 @@ fn handle_download_output_line(
             }
         };
 
-        if let Some(item) = state.queue.iter_mut().find(|queued| queued.id == job_id) {
-            let can_update_transfer_state = item.status == "queued" || item.status == "downloading";
-            if !can_update_transfer_state {
-                return;
-            }
+        let Some(item) = state
+            .queue
+            .iter_mut()
+            .find(|queued| queued.id == throttle.job_id)
+        else {
+            return;
+        };
+        let can_update_transfer_state = item.status == "queued" || item.status == "downloading";
+        if !can_update_transfer_state {
+            return;
+        }
 
-            should_emit |= append_download_log(item, normalized);
+        if append_download_log(item, normalized) {
+            appended.push(normalized.to_string());
+            should_emit = true;
+        }
 
-            if (normalized.contains("ERROR:") || normalized.contains("HTTP Error"))
-                && item.error_message.as_deref() != Some(normalized)
-            {
-                item.error_message = Some(normalized.to_string());
+        if (normalized.contains("ERROR:") || normalized.contains("HTTP Error"))
+            && item.error_message.as_deref() != Some(normalized)
+        {
+            item.error_message = Some(normalized.to_string());
+            should_emit = true;
+        }
+        if let Some(progress) = parse_progress_percent(normalized) {
+            if (item.progress_percent - progress).abs() > f64::EPSILON {
+                item.progress_percent = progress;
                 should_emit = true;
             }
-            if let Some(progress) = parse_progress_percent(normalized) {
-                if (item.progress_percent - progress).abs() > f64::EPSILON {
-                    item.progress_percent = progress;
-                    should_emit = true;
-                }
-                if item.status != "downloading" {
-                    item.status = "downloading".to_string();
-                    should_emit = true;
-                }
+            if item.status != "downloading" {
+                item.status = "downloading".to_string();
+                should_emit = true;
             }
-            if let Some(speed) = parse_speed(normalized) {
-                if item.speed_text.as_deref() != Some(speed.as_str()) {
-                    item.speed_text = Some(speed);
-                    should_emit = true;
-                }
+        }
+        if let Some(speed) = parse_speed(normalized) {
+            if item.speed_text.as_deref() != Some(speed.as_str()) {
+                item.speed_text = Some(speed);
+                should_emit = true;
             }
-            if let Some(eta) = parse_eta(normalized) {
-                if item.eta_text.as_deref() != Some(eta.as_str()) {
-                    item.eta_text = Some(eta);
-                    should_emit = true;
-                }
+        }
+        if let Some(eta) = parse_eta(normalized) {
+            if item.eta_text.as_deref() != Some(eta.as_str()) {
+                item.eta_text = Some(eta);
+                should_emit = true;
             }
         }
+        if let Some(count) = parse_sponsorblock_segment_count(normalized) {
+            let summary = format!("SponsorBlock: {count} segment(s) found");
+            item.sponsorblock_segments = Some(count);
+            if append_download_log(item, &summary) {
+                appended.push(summary);
+            }
+            should_emit = true;
+        }
 
         if should_emit {
-            Some(queue_snapshot(&state))
+            throttle.record(item, appended, false)
         } else {
             None
         }
     };
 
-    if let Some(snapshot) = snapshot_to_emit {
-        emit_queue_updated_snapshot(app, snapshot);
+    if let Some(update) = update_to_emit {
+        host.emit_job(update);
     }
 }
 
 // ============================================================================
-// Worker thread
+// Worker pool
 // ============================================================================
 
-// @MX:ANCHOR: Main download worker entry point — called by queue commands (enqueue, resume)
-// and by lib.rs setup for queue recovery. All yt-dlp spawning flows through here.
-// @MX:REASON: Called from queue::enqueue_job, queue::resume_job, and lib.rs setup; fan_in >= 3.
-pub fn start_worker_if_needed(
-    app: AppHandle,
-    shared: Arc<Mutex<crate::settings::AppState>>,
+/// Upper bound for `max_concurrent_downloads`. Each worker runs its own yt-dlp
+/// and ffmpeg processes, and YouTube throttles clients that open many streams
+/// at once, so more workers rarely finish a queue sooner.
+pub const MAX_CONCURRENT_DOWNLOADS: usize = 6;
+
+/// Clamps a requested worker count to `1..=MAX_CONCURRENT_DOWNLOADS`.
+pub fn clamp_worker_count(size: usize) -> usize {
+    size.clamp(1, MAX_CONCURRENT_DOWNLOADS)
+}
+
+/// Messages handled by the worker pool dispatcher thread.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PoolMessage {
+    /// A job was enqueued or resumed; dispatch it if a worker is free.
+    JobQueued,
+    /// A worker finished the job with this id and is idle again.
+    JobFinished(String),
+    /// `max_concurrent_downloads` changed; grow or shrink the pool.
+    Resize(usize),
+    /// Stop dispatching and let every worker exit after its current job.
+    Shutdown,
+}
+
+/// Commands sent from the dispatcher to pool workers.
+enum WorkerCommand {
+    Run(Box<QueueItem>),
+    Exit,
+}
+
+/// Handle to the running download worker pool.
+///
+/// A single dispatcher thread owns the workers. It claims queued jobs and hands
+/// them to idle workers over a channel, so concurrency changes take effect
+/// without waiting for a new enqueue.
+pub struct WorkerPool {
+    tx: Sender<PoolMessage>,
+    dispatcher: Option<JoinHandle<()>>,
+}
+
+impl WorkerPool {
+    /// Starts the dispatcher with `size` workers and dispatches any queued jobs.
+    pub fn start(
+        host: Arc<dyn WorkerHost>,
+        backend: Arc<dyn DownloadBackend>,
+        shared: Arc<Mutex<AppState>>,
+        runtime: Arc<Mutex<RuntimeState>>,
+        size: usize,
+    ) -> Self {
+        let (tx, rx) = channel::<PoolMessage>();
+        let events = tx.clone();
+        let dispatcher = std::thread::spawn(move || {
+            let dispatcher = Dispatcher {
+                host,
+                backend,
+                shared,
+                runtime,
+                events,
+            };
+            dispatcher.run(rx, size);
+        });
+        let _ = tx.send(PoolMessage::JobQueued);
+        Self {
+            tx,
+            dispatcher: Some(dispatcher),
+        }
+    }
+
+    /// Tells the dispatcher that queued jobs may be waiting.
+    pub fn notify(&self) {
+        let _ = self.tx.send(PoolMessage::JobQueued);
+    }
+
+    /// Changes the number of workers. Busy workers finish their job before exiting.
+    pub fn resize(&self, size: usize) {
+        let _ = self.tx.send(PoolMessage::Resize(size));
+    }
+
+    /// Stops dispatching and returns the dispatcher handle. Joining it waits
+    /// for every worker to finish its current job and exit.
+    pub fn shutdown(mut self) -> Option<JoinHandle<()>> {
+        let _ = self.tx.send(PoolMessage::Shutdown);
+        self.dispatcher.take()
+    }
+}
+
+struct Dispatcher {
+    host: Arc<dyn WorkerHost>,
+    backend: Arc<dyn DownloadBackend>,
+    shared: Arc<Mutex<AppState>>,
     runtime: Arc<Mutex<RuntimeState>>,
-) {
-    let should_start = {
-        let mut state = lock_or_recover(&shared, "start_worker_if_needed/should_start");
-        let max_concurrent = state.settings.max_concurrent_downloads.clamp(1, 3) as usize;
-        if state.active_worker_count >= max_concurrent {
-            false
-        } else if state.queue.iter().any(|item| item.status == "queued") {
-            state.active_worker_count += 1;
-            true
-        } else {
-            false
+    events: Sender<PoolMessage>,
+}
+
+impl Dispatcher {
+    fn run(self, rx: Receiver<PoolMessage>, size: usize) {
+        let (job_tx, job_rx) = channel::<WorkerCommand>();
+        let job_rx = Arc::new(Mutex::new(job_rx));
+        let mut handles: Vec<JoinHandle<()>> = Vec::new();
+        // Ids of jobs handed to a worker. A job waiting between retries is
+        // "queued" again but must not be dispatched to a second worker.
+        let mut in_flight: HashSet<String> = HashSet::new();
+        let mut capacity: usize = 0;
+
+        self.resize(&job_tx, &job_rx, &mut handles, &mut capacity, size);
+
+        while let Ok(message) = rx.recv() {
+            match message {
+                PoolMessage::JobQueued => {}
+                PoolMessage::JobFinished(job_id) => {
+                    in_flight.remove(&job_id);
+                }
+                PoolMessage::Resize(size) => {
+                    self.resize(&job_tx, &job_rx, &mut handles, &mut capacity, size);
+                }
+                PoolMessage::Shutdown => break,
+            }
+            self.dispatch_queued_jobs(&job_tx, &mut in_flight, capacity);
         }
-    };
 
-    if !should_start {
-        return;
+        eprintln!(
+            "[STABILITY] Worker pool shutting down; waiting for {} busy worker(s)",
+            in_flight.len()
+        );
+        for _ in 0..capacity {
+            let _ = job_tx.send(WorkerCommand::Exit);
+        }
+        drop(job_tx);
+        for handle in handles {
+            let _ = handle.join();
+        }
+        let mut state = lock_or_recover(&self.shared, "worker_pool/shutdown");
+        state.active_worker_count = 0;
     }
 
-    let (shutdown_tx, shutdown_rx) = std::sync::mpsc::channel::<()>();
-    {
-        let mut rt = lock_or_recover(&runtime, "start_worker_if_needed/shutdown_tx");
-        rt.shutdown_txs.push(shutdown_tx);
-    }
-
-    let runtime_for_thread = runtime.clone();
-    let handle = std::thread::spawn(move || {
-        let runtime = runtime_for_thread;
-        loop {
-            if shutdown_rx.try_recv().is_ok() {
-                eprintln!("[STABILITY] Worker thread received shutdown signal; exiting");
-                let mut state = lock_or_recover(&shared, "worker_thread/shutdown");
-                state.active_worker_count = state.active_worker_count.saturating_sub(1);
-                return;
+    fn resize(
+        &self,
+        job_tx: &Sender<WorkerCommand>,
+        job_rx: &Arc<Mutex<Receiver<WorkerCommand>>>,
+        handles: &mut Vec<JoinHandle<()>>,
+        capacity: &mut usize,
+        size: usize,
+    ) {
+        let size = clamp_worker_count(size);
+        handles.retain(|handle| !handle.is_finished());
+        if size > *capacity {
+            for _ in *capacity..size {
+                handles.push(self.spawn_worker(job_rx.clone()));
             }
-            let current_job = {
-                let mut state = lock_or_recover(&shared, "worker_thread/current_job");
-                let next_index = state.queue.iter().position(|item| item.status == "queued");
-                if let Some(index) = next_index {
-                    state.queue[index].status = "downloading".to_string();
-                    state.queue[index].progress_percent = 0.0;
-                    let job = state.queue[index].clone();
-                    emit_queue_updated(&app, &state);
-                    Some(job)
-                } else {
-                    state.active_worker_count = state.active_worker_count.saturating_sub(1);
-                    emit_queue_updated(&app, &state);
-                    None
-                }
+        } else {
+            // Queued ahead of any new Run, so the surplus workers pick these up
+            // as soon as they are idle.
+            for _ in size..*capacity {
+                let _ = job_tx.send(WorkerCommand::Exit);
+            }
+        }
+        *capacity = size;
+    }
+
+    fn spawn_worker(&self, jobs: Arc<Mutex<Receiver<WorkerCommand>>>) -> JoinHandle<()> {
+        let host = self.host.clone();
+        let backend = self.backend.clone();
+        let shared = self.shared.clone();
+        let runtime = self.runtime.clone();
+        let events = self.events.clone();
+        std::thread::spawn(move || loop {
+            let command = {
+                let receiver = lock_or_recover(&jobs, "worker_pool/recv");
+                receiver.recv()
             };
+            match command {
+                Ok(WorkerCommand::Run(job)) => {
+                    process_job(host.as_ref(), backend.as_ref(), &shared, &runtime, &job);
+                    let _ = events.send(PoolMessage::JobFinished(job.id));
+                }
+                Ok(WorkerCommand::Exit) | Err(_) => return,
+            }
+        })
+    }
 
-            let Some(job) = current_job else {
-                return;
+    /// Claims queued jobs while there are idle workers and hands them out.
+    fn dispatch_queued_jobs(
+        &self,
+        job_tx: &Sender<WorkerCommand>,
+        in_flight: &mut HashSet<String>,
+        capacity: usize,
+    ) {
+        let mut state = lock_or_recover(&self.shared, "worker_pool/dispatch");
+        if state.queue_pause_reason.is_some() {
+            return;
+        }
+        while in_flight.len() < capacity {
+            let Some(item) = state
+                .queue
+                .iter_mut()
+                .find(|item| item.status == "queued" && !in_flight.contains(&item.id))
+            else {
+                break;
             };
+            item.status = "downloading".to_string();
+            item.progress_percent = 0.0;
+            let job = item.clone();
+            in_flight.insert(job.id.clone());
+            self.host.emit_job(JobUpdate::from_item(&job, Vec::new()));
+            if job_tx.send(WorkerCommand::Run(Box::new(job))).is_err() {
+                break;
+            }
+        }
+        state.active_worker_count = in_flight.len();
+    }
+}
+
+// @MX:ANCHOR: Starts the download worker pool — called once from lib.rs setup.
+// @MX:REASON: The pool is the only place yt-dlp jobs are dispatched from.
+pub fn start_worker_pool(
+    app: AppHandle,
+    shared: Arc<Mutex<AppState>>,
+    runtime: Arc<Mutex<RuntimeState>>,
+) {
+    let size = {
+        let state = lock_or_recover(&shared, "start_worker_pool/size");
+        state.settings.max_concurrent_downloads.max(1) as usize
+    };
+    let host: Arc<dyn WorkerHost> = Arc::new(TauriWorkerHost::new(app.clone()));
+    let backend: Arc<dyn DownloadBackend> = Arc::new(YtDlpBackend::new(app));
+    let pool = WorkerPool::start(host, backend, shared, runtime.clone(), size);
+    let mut rt = lock_or_recover(&runtime, "start_worker_pool/store");
+    rt.pool = Some(pool);
+}
+
+// @MX:ANCHOR: Wakes the dispatcher after a job becomes queued.
+// @MX:REASON: Called from queue::enqueue_job and queue::resume_job.
+pub fn notify_worker_pool(runtime: &Arc<Mutex<RuntimeState>>) {
+    let rt = lock_or_recover(runtime, "notify_worker_pool");
+    if let Some(pool) = rt.pool.as_ref() {
+        pool.notify();
+    }
+}
+
+/// Applies a new `max_concurrent_downloads` value to the running pool.
+pub fn resize_worker_pool(runtime: &Arc<Mutex<RuntimeState>>, size: usize) {
+    let rt = lock_or_recover(runtime, "resize_worker_pool");
+    if let Some(pool) = rt.pool.as_ref() {
+        pool.resize(size);
+    }
+}
+
+// @MX:NOTE: Shutdown order matters: the flag is set before children are terminated so
+// workers see their failed attempt as an interruption, not as a download error to retry.
+/// Stops the worker pool for app exit: no new jobs are dispatched, running
+/// yt-dlp processes are terminated gracefully, and their jobs are requeued
+/// with partial data kept. Waits up to `timeout` for workers to finish and
+/// returns false if some were still busy.
+pub fn shutdown_worker_pool(
+    shared: &Arc<Mutex<AppState>>,
+    runtime: &Arc<Mutex<RuntimeState>>,
+    timeout: Duration,
+) -> bool {
+    let pool = {
+        let mut rt = lock_or_recover(runtime, "shutdown_worker_pool/flag");
+        rt.shutting_down = true;
+        rt.pool.take()
+    };
+    let dispatcher = pool.and_then(WorkerPool::shutdown);
+    kill_active_child_unchecked(runtime);
 
-            if let Some(dependency) = app.try_state::<crate::dependencies::SharedDependencyState>()
+    let Some(dispatcher) = dispatcher else {
+        return true;
+    };
+    let deadline = Instant::now() + timeout;
+    while !dispatcher.is_finished() {
+        if Instant::now() >= deadline {
+            // Workers still busy (e.g. probing or waiting on dependencies):
+            // requeue their jobs so the final queue write is resumable.
+            let mut state = lock_or_recover(shared, "shutdown_worker_pool/timeout");
+            for item in state
+                .queue
+                .iter_mut()
+                .filter(|item| item.status == "downloading")
             {
-                if let Err(err) = crate::dependencies::wait_for_dependencies(&app, &dependency.0) {
-                    let mut state = lock_or_recover(&shared, "worker_thread/wait_for_deps_failure");
-                    if let Some(item) = state.queue.iter_mut().find(|item| item.id == job.id) {
-                        item.status = "failed".to_string();
-                        item.error_message = Some(err);
-                    }
-                    emit_queue_updated(&app, &state);
-                    persist_queue(&app, &state);
-                    continue;
-                }
+                mark_interrupted(item);
             }
+            eprintln!("[STABILITY] Worker pool did not stop within {timeout:?}");
+            return false;
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    let _ = dispatcher.join();
+    true
+}
 
-            let (download_dir, final_output_path, max_retries) = {
-                let state = lock_or_recover(&shared, "worker_thread/download_setup");
-                let path = build_unique_output_path(&state, &job.title, &job.mode);
-                (
-                    state.settings.download_dir.clone(),
-                    path,
-                    state.settings.max_retries.max(0) as usize,
-                )
-            };
+/// Runs one claimed job once dependencies are ready.
+fn process_job(
+    host: &dyn WorkerHost,
+    backend: &dyn DownloadBackend,
+    shared: &Arc<Mutex<AppState>>,
+    runtime: &Arc<Mutex<RuntimeState>>,
+    job: &QueueItem,
+) {
+    if let Err(err) = host.wait_for_dependencies() {
+        let mut state = lock_or_recover(shared, "worker_thread/wait_for_deps_failure");
+        if let Some(item) = state.queue.iter_mut().find(|item| item.id == job.id) {
+            item.status = "failed".to_string();
+            item.error_message = Some(err);
+            host.emit_job(JobUpdate::from_item(item, Vec::new()));
+        }
+        let saved = host.persist_queue(&state, PersistMode::Immediate);
+        drop(state);
+        saved.wait();
+        return;
+    }
 
-            let _ = fs::create_dir_all(&download_dir);
-            let temp_dir = temp_job_dir_path(&app, &job.id);
-            let _ = fs::create_dir_all(&temp_dir);
-            let output_template = temp_dir.join("media.%(ext)s");
-
-            let format_expr = select_format_expression(&job.mode, &job.quality_id);
-            let mut args = vec![
-                "--no-playlist".to_string(),
-                "--newline".to_string(),
-                "--progress".to_string(),
-                "--socket-timeout".to_string(),
-                "30".to_string(),
-                "--fragment-retries".to_string(),
-                "10".to_string(),
-                "--throttled-rate".to_string(),
-                "100K".to_string(),
-                "--extractor-retries".to_string(),
-                "5".to_string(),
-                "--concurrent-fragments".to_string(),
-                "4".to_string(),
-                "-f".to_string(),
-                format_expr,
-                "-o".to_string(),
-                output_template.to_string_lossy().to_string(),
-                job.url.clone(),
-            ];
-
-            match job.mode {
-                crate::metadata::DownloadMode::Audio => {
-                    args.push("-x".to_string());
-                    args.push("--audio-format".to_string());
-                    args.push("mp3".to_string());
-                }
-                crate::metadata::DownloadMode::Video => {
-                    args.push("--merge-output-format".to_string());
-                    args.push("mp4".to_string());
-                    args.push("--recode-video".to_string());
-                    args.push("mp4".to_string());
+    run_job(host, backend, shared, runtime, job);
+}
+
+/// Runs every attempt of a single job: spawn, stream output, then move the
+/// result into place or schedule a retry according to the error class.
+fn run_job(
+    host: &dyn WorkerHost,
+    backend: &dyn DownloadBackend,
+    shared: &Arc<Mutex<AppState>>,
+    runtime: &Arc<Mutex<RuntimeState>>,
+    job: &QueueItem,
+) {
+    let (
+        temp_dir,
+        output_template,
+        download_dir,
+        output_target,
+        max_retries,
+        retry_policy,
+        reserve_bytes,
+        args,
+        expected_duration,
+    ) = {
+        let state = lock_or_recover(shared, "worker_thread/download_setup");
+        let download_dir = job_download_dir(&state.settings, job);
+        let temp_dir = match state.settings.temp_location {
+            TempLocation::AppData => host.temp_job_dir(&job.id),
+            TempLocation::DestinationVolume => prepare_staging_dir(&download_dir, &job.id),
+        };
+        let output_template = temp_dir.join("media.%(ext)s");
+        let args = build_download_args(job, &state.settings, &output_template);
+        let target = resolve_output_target(&state, job);
+        // Cutting SponsorBlock segments shortens the output on purpose.
+        let segments_removed = job
+            .sponsorblock_mode
+            .unwrap_or(state.settings.sponsorblock_mode)
+            == SponsorBlockMode::Remove;
+        (
+            temp_dir,
+            output_template,
+            download_dir,
+            target,
+            state.settings.max_retries.max(0) as usize,
+            state.settings.retry_policy.clone(),
+            state.settings.min_free_space_mb.saturating_mul(1024 * 1024),
+            args,
+            job.duration_sec.filter(|_| !segments_removed),
+        )
+    };
+
+    let final_output_path = match output_target {
+        OutputTarget::Write(path) => path,
+        OutputTarget::Conflict(path) => {
+            fail_output_conflict(host, shared, &job.id, &path);
+            remove_directory_safe(&temp_dir);
+            return;
+        }
+        OutputTarget::Existing(path) => {
+            match check_existing_output(host, &path, job, expected_duration) {
+                Some(verdict) => {
+                    complete_from_existing(host, shared, &job.id, &path, verdict);
+                    remove_directory_safe(&temp_dir);
+                    return;
                 }
+                // A partial or broken copy is replaced instead of suffixed.
+                None => path,
             }
+        }
+    };
+
+    let shortfall = disk_space_shortfall(
+        job.estimated_bytes,
+        reserve_bytes,
+        &[
+            (temp_dir.as_path(), host.available_space(&temp_dir)),
+            (download_dir.as_path(), host.available_space(&download_dir)),
+        ],
+    );
+    if let Some(reason) = shortfall {
+        pause_queue_for_low_space(host, shared, &job.id, reason);
+        return;
+    }
+
+    let _ = fs::create_dir_all(&download_dir);
+    let _ = fs::create_dir_all(&temp_dir);
 
-            let mut attempt: usize = 0;
-            loop {
+    let mut request = DownloadRequest {
+        job_id: job.id.clone(),
+        args,
+        temp_dir: temp_dir.clone(),
+    };
+
+    let throttle = JobUpdateThrottle::new(&job.id);
+    let mut finished: Option<QueueItem> = None;
+    let mut attempt: usize = 0;
+    // The format fallback is tried at most once per run.
+    let mut current_job = job.clone();
+    let mut format_fallback_tried = false;
+    let mut interrupted = false;
+    let mut move_resumable = false;
+    loop {
+        if job_stopped(shared, &job.id) {
+            break;
+        }
+        if is_shutting_down(runtime) {
+            interrupted = true;
+            requeue_interrupted_job(host, shared, &throttle, &job.id);
+            break;
+        }
+
+        // Read per attempt so a changed setting applies to the next retry.
+        let stall_timeout_sec = lock_or_recover(shared, "worker_thread/stall_timeout")
+            .settings
+            .stall_timeout_sec;
+        let mut stalled = false;
+        let (process_ok, process_error): (bool, Option<String>) = match backend.spawn(&request) {
+            Ok(mut process) => {
+                let streams = process.take_output_streams();
+                let process = Arc::new(Mutex::new(process));
                 {
-                    let state = lock_or_recover(&shared, "worker_thread/retry_loop_stop_check");
-                    let stopped = state
-                        .queue
-                        .iter()
-                        .find(|item| item.id == job.id)
-                        .map(|item| item.status == "paused" || item.status == "canceled")
-                        .unwrap_or(true);
-                    if stopped {
-                        break;
+                    let mut guard =
+                        lock_or_recover(runtime, "worker_thread/active_processes_insert");
+                    // Shutdown may have drained the active processes between
+                    // the loop check and this spawn; stop the straggler here.
+                    if guard.shutting_down {
+                        lock_or_recover(&process, "worker_thread/shutdown_straggler").terminate();
                     }
+                    guard.active_processes.insert(
+                        job.id.clone(),
+                        ActiveProcess {
+                            job_id: job.id.clone(),
+                            child: process.clone(),
+                        },
+                    );
                 }
 
-                let yt_dlp = resolve_executable(&app, "yt-dlp");
-                let mut cmd = Command::new(&yt_dlp);
-                configure_hidden_process(&mut cmd);
-                let spawn_result = cmd
-                    .args(args.clone())
-                    .env("PATH", managed_path_env(&app))
-                    .stdout(Stdio::piped())
-                    .stderr(Stdio::piped())
-                    .spawn();
-
-                let (process_ok, process_error): (bool, Option<String>) = match spawn_result {
-                    Ok(mut child) => {
-                        let stdout_reader = child.stdout.take().map(BufReader::new);
-                        let stderr_reader = child.stderr.take().map(BufReader::new);
-
-                        let child_arc = Arc::new(Mutex::new(child));
-                        {
-                            let mut guard =
-                                lock_or_recover(&runtime, "worker_thread/active_processes_insert");
-                            guard.active_processes.insert(
-                                job.id.clone(),
-                                ActiveProcess {
-                                    job_id: job.id.clone(),
-                                    child: child_arc.clone(),
-                                },
-                            );
-                        }
-
-                        let shared_stdout = shared.clone();
-                        let app_stdout = app.clone();
-                        let job_id_stdout = job.id.clone();
-                        let stdout_thread = stdout_reader.map(|reader| {
-                            std::thread::spawn(move || {
-                                for line in reader.lines().map_while(Result::ok) {
-                                    handle_download_output_line(
-                                        &shared_stdout,
-                                        &app_stdout,
-                                        &job_id_stdout,
-                                        &line,
-                                    );
-                                }
-                            })
-                        });
-
-                        let shared_stderr = shared.clone();
-                        let app_stderr = app.clone();
-                        let job_id_stderr = job.id.clone();
-                        let stderr_thread = stderr_reader.map(|reader| {
-                            std::thread::spawn(move || {
-                                for line in reader.lines().map_while(Result::ok) {
-                                    handle_download_output_line(
-                                        &shared_stderr,
-                                        &app_stderr,
-                                        &job_id_stderr,
-                                        &line,
-                                    );
-                                }
-                            })
+                // Reader threads are scoped so they are joined before the
+                // attempt result is evaluated.
+                let throttle = &throttle;
+                let watchdog = &StallWatchdog::new(stall_timeout_sec, Instant::now());
+                let stalled = &mut stalled;
+                let wait_result = std::thread::scope(|scope| {
+                    for stream in streams {
+                        scope.spawn(move || {
+                            for line in BufReader::new(stream).lines().map_while(Result::ok) {
+                                watchdog.observe_line(&line, Instant::now());
+                                handle_download_output_line(shared, host, throttle, &line);
+                            }
                         });
+                    }
 
-                        let wait_result = loop {
-                            let status = {
-                                let mut locked_child =
-                                    lock_or_recover(&child_arc, "worker_thread/child_try_wait");
-                                locked_child.try_wait()
-                            };
-
-                            match status {
-                                Ok(Some(exit_status)) => break Ok(exit_status),
-                                Ok(None) => std::thread::sleep(Duration::from_millis(100)),
-                                Err(err) => break Err(err),
-                            }
+                    let bytes_interval = Duration::from_millis(STALL_BYTES_CHECK_INTERVAL_MS);
+                    let mut next_bytes_check = Instant::now();
+                    loop {
+                        let status = {
+                            let mut locked_child =
+                                lock_or_recover(&process, "worker_thread/child_try_wait");
+                            locked_child.try_wait()
                         };
 
-                        if let Some(handle) = stdout_thread {
-                            let _ = handle.join();
-                        }
-                        if let Some(handle) = stderr_thread {
-                            let _ = handle.join();
+                        match status {
+                            Ok(Some(success)) => break Ok(success),
+                            Ok(None) => {
+                                throttle.flush_due(shared, host);
+                                let now = Instant::now();
+                                if now >= next_bytes_check {
+                                    watchdog
+                                        .observe_bytes(calculate_directory_size(&temp_dir), now);
+                                    next_bytes_check = now + bytes_interval;
+                                }
+                                if !*stalled && watchdog.is_stalled(now) {
+                                    *stalled = true;
+                                    lock_or_recover(&process, "worker_thread/stall_terminate")
+                                        .terminate();
+                                }
+                                std::thread::sleep(Duration::from_millis(100));
+                            }
+                            Err(err) => break Err(err),
                         }
+                    }
+                });
 
-                        clear_active_process(&runtime, &job.id);
+                clear_active_process(runtime, &job.id);
 
-                        match wait_result {
-                            Ok(exit_status) => (exit_status.success(), None),
-                            Err(err) => (false, Some(err.to_string())),
-                        }
-                    }
+                match wait_result {
+                    Ok(_) if *stalled => (false, Some(stalled_message(stall_timeout_sec))),
+                    Ok(success) => (success, None),
                     Err(err) => (false, Some(err.to_string())),
-                };
+                }
+            }
+            Err(err) => (false, Some(err)),
+        };
+        // Read before the state lock: RuntimeState is never locked inside it.
+        interrupted = !process_ok && is_shutting_down(runtime);
 
-                let mut should_retry = false;
-                let mut should_retry_strategy = RetryStrategy::Default;
-                {
-                    let mut state = lock_or_recover(&shared, "worker_thread/retry_result");
-                    if let Some(item) = state.queue.iter_mut().find(|item| item.id == job.id) {
-                        if item.status == "paused" || item.status == "canceled" {
-                            // Keep paused/canceled state as-is.
-                        } else if process_ok {
-                            let expected_ext = expected_extension(&job.mode);
-                            let move_result = resolve_downloaded_file_path(&temp_dir, expected_ext)
-                                .and_then(|completed_path| {
-                                    move_file_atomic(&completed_path, &final_output_path)
-                                });
-                            match move_result {
-                                Ok(()) => {
-                                    item.status = "completed".to_string();
-                                    item.progress_percent = 100.0;
-                                    item.output_path =
-                                        Some(final_output_path.to_string_lossy().to_string());
-                                    item.error_message = None;
-                                }
-                                Err(err) => {
-                                    item.status = "failed".to_string();
-                                    item.error_message = Some(err);
-                                }
-                            }
-                        } else {
-                            let fallback =
-                                process_error.unwrap_or_else(|| "다운로드 실패".to_string());
-                            item.error_message = Some(fallback.clone());
-                            let strategy = classify_download_error(&fallback);
-                            if strategy == RetryStrategy::NoRetry {
-                                item.status = "failed".to_string();
-                            } else if attempt < max_retries {
-                                should_retry = true;
-                                should_retry_strategy = strategy;
-                                item.retry_count = (attempt + 1) as i32;
-                                item.status = "queued".to_string();
-                                item.speed_text = None;
-                                item.eta_text = None;
-                            } else {
-                                item.status = "failed".to_string();
-                            }
+        // Move and probe the output outside the state lock; ffprobe can take
+        // a while on large files.
+        let finalized = if process_ok && !job_stopped(shared, &job.id) {
+            Some(finalize_output(
+                host,
+                shared,
+                &throttle,
+                &temp_dir,
+                &final_output_path,
+                &job.mode,
+                expected_duration,
+            ))
+        } else {
+            None
+        };
+        // A failed cross-device copy keeps its source so a retry can resume it.
+        move_resumable = matches!(finalized, Some(Err(_)))
+            && incomplete_marker_path(&final_output_path).exists();
+
+        // A vanished format is resolved against a fresh format list before
+        // the failure is recorded; fetching it happens outside the lock.
+        let substitution = if !process_ok && !interrupted && !format_fallback_tried {
+            let (error, settings) = {
+                let state = lock_or_recover(shared, "worker_thread/format_fallback_check");
+                let error = process_error.clone().or_else(|| {
+                    state
+                        .queue
+                        .iter()
+                        .find(|item| item.id == job.id)
+                        .and_then(|item| item.error_message.clone())
+                });
+                (error, state.settings.clone())
+            };
+            let format_missing = error
+                .map(|error| classify_download_error(&error) == RetryStrategy::FormatUnavailable)
+                .unwrap_or(false);
+            if format_missing {
+                format_fallback_tried = true;
+                resolve_format_fallback(host, &current_job, &settings)
+            } else {
+                None
+            }
+        } else {
+            None
+        };
+
+        let mut log_lines: Vec<String> = Vec::new();
+        let mut should_retry = false;
+        let mut should_retry_strategy = RetryStrategy::Default;
+        let mut disk_full_reason: Option<String> = None;
+        {
+            let mut state = lock_or_recover(shared, "worker_thread/retry_result");
+            let mut update = None;
+            let mut mode = PersistMode::Immediate;
+            if let Some(item) = state.queue.iter_mut().find(|item| item.id == job.id) {
+                if item.status == "paused" || item.status == "canceled" {
+                    // Keep paused/canceled state as-is.
+                } else if interrupted {
+                    mark_interrupted(item);
+                    log_lines.push(INTERRUPTED_LOG_LINE.to_string());
+                } else if process_ok {
+                    match finalized {
+                        Some(Ok(verdict)) => {
+                            item.progress_percent = 100.0;
+                            item.move_progress_percent = None;
+                            item.error_category = None;
+                            item.output_path =
+                                Some(final_output_path.to_string_lossy().to_string());
+                            item.output_bytes =
+                                fs::metadata(&final_output_path).ok().map(|meta| meta.len());
+                            log_lines.push(apply_integrity_verdict(item, verdict));
+                        }
+                        Some(Err(err)) => {
+                            item.status = "failed".to_string();
+                            item.move_progress_percent = None;
+                            item.error_message = Some(err);
+                        }
+                        // Paused or canceled just as the process exited.
+                        None => {}
+                    }
+                } else if let Some(substitution) = &substitution {
+                    log_lines.push(format!(
+                        "[format] {} is no longer available, using {} ({})",
+                        item.quality_id, substitution.format_id, substitution.label
+                    ));
+                    item.quality_id = substitution.format_id.clone();
+                    item.status = "queued".to_string();
+                    item.error_message = None;
+                    item.speed_text = None;
+                    item.eta_text = None;
+                    mode = PersistMode::Deferred;
+                } else {
+                    // Prefer the ERROR: line captured from yt-dlp output so the
+                    // classifier sees the real cause, not the generic fallback.
+                    let fallback = process_error
+                        .clone()
+                        .or_else(|| item.error_message.clone())
+                        .unwrap_or_else(|| "다운로드 실패".to_string());
+                    item.error_message = Some(fallback.clone());
+                    // A stalled connection is retried like a dropped one.
+                    let strategy = if stalled {
+                        log_lines.push(format!(
+                            "[watchdog] stalled: no progress for {stall_timeout_sec}s; restarting"
+                        ));
+                        RetryStrategy::NetworkError
+                    } else {
+                        classify_download_error(&fallback)
+                    };
+                    item.error_category = Some(strategy);
+                    if strategy == RetryStrategy::DiskFull {
+                        // Retrying cannot help until space is freed; hold the
+                        // whole queue like the preflight does.
+                        let reason = disk_full_message(&fallback);
+                        item.status = "queued".to_string();
+                        item.progress_percent = 0.0;
+                        item.error_message = Some(reason.clone());
+                        log_lines.push(reason.clone());
+                        disk_full_reason = Some(reason);
+                    } else if attempt < retry_policy.max_retries(strategy, max_retries) {
+                        should_retry = true;
+                        should_retry_strategy = strategy;
+                        item.retry_count = (attempt + 1) as i32;
+                        item.status = "queued".to_string();
+                        item.speed_text = None;
+                        item.eta_text = None;
+                        mode = PersistMode::Deferred;
+                    } else {
+                        item.status = "failed".to_string();
+                        if matches!(strategy, RetryStrategy::NeedsAuth | RetryStrategy::BotCheck) {
+                            item.error_message = Some(needs_auth_message(&fallback));
                         }
                     }
-                    emit_queue_updated(&app, &state);
-                    persist_queue(&app, &state);
                 }
-
-                if should_retry {
-                    attempt += 1;
-                    std::thread::sleep(Duration::from_millis(retry_delay_ms_for_strategy(
-                        &should_retry_strategy,
-                        attempt,
-                    )));
-                    continue;
+                let appended: Vec<String> = std::mem::take(&mut log_lines)
+                    .into_iter()
+                    .filter(|line| append_download_log(item, line))
+                    .collect();
+                update = throttle.record(item, appended, true);
+                if item.status == "completed" || item.status == "failed" {
+                    finished = Some(item.clone());
                 }
-                break;
             }
+            if let Some(reason) = disk_full_reason {
+                eprintln!("[STABILITY] {reason}");
+                state.queue_pause_reason = Some(reason);
+                host.emit_queue(&state);
+            }
+            let saved = host.persist_queue(&state, mode);
+            drop(state);
+            saved.wait();
+            if let Some(update) = update {
+                host.emit_job(update);
+            }
+        }
 
-            remove_directory_safe(&temp_dir);
+        if let Some(substitution) = substitution {
+            current_job.quality_id = substitution.format_id;
+            let settings = lock_or_recover(shared, "worker_thread/format_fallback_args")
+                .settings
+                .clone();
+            request.args = build_download_args(&current_job, &settings, &output_template);
+            continue;
         }
-    });
 
-    {
-        let mut rt = lock_or_recover(&runtime, "start_worker_if_needed/worker_handle");
-        rt.worker_handles.push(handle);
+        if should_retry {
+            // `attempt` counts retries already made, so the first retry uses entry 0.
+            std::thread::sleep(Duration::from_millis(
+                retry_policy.delay_ms(should_retry_strategy, attempt),
+            ));
+            attempt += 1;
+            continue;
+        }
+        break;
+    }
+
+    // Partial data of paused or interrupted jobs lets yt-dlp continue where
+    // it stopped when the job is resumed.
+    if !interrupted && !move_resumable && !job_status_is(shared, &job.id, "paused") {
+        remove_directory_safe(&temp_dir);
+    }
+
+    if let Some(finished) = finished {
+        run_post_download_hooks(host, shared, &throttle, &finished);
+    }
+}
+
+// ============================================================================
+// Format fallback
+// ============================================================================
+
+/// Looks up a replacement for a job whose format is no longer offered.
+/// Returns `None` when the format list cannot be fetched or is empty.
+fn resolve_format_fallback(
+    host: &dyn WorkerHost,
+    job: &QueueItem,
+    settings: &AppSettings,
+) -> Option<FormatSubstitution> {
+    let extra_args = [
+        job_cookie_args(job, settings),
+        proxy_args(settings.proxy_url.as_deref()),
+    ]
+    .concat();
+    match host.fetch_formats(&job.url, &extra_args) {
+        Ok(payload) => find_fallback_format(
+            &payload,
+            &job.mode,
+            &job.quality_id,
+            job.format_profile.as_ref(),
+        ),
+        Err(err) => {
+            eprintln!(
+                "[STABILITY] format fallback lookup failed for {}: {err}",
+                job.id
+            );
+            None
+        }
+    }
+}
+
+// ============================================================================
+// Output collisions
+// ============================================================================
+
+/// Existing files smaller than this share of the analyzed size are treated
+/// as partial when ffprobe is unavailable.
+const SKIP_MIN_SIZE_RATIO: f64 = 0.9;
+
+/// Decides whether a file already at a skip-policy job's destination is a
+/// finished copy. Returns its verdict (`None` when ffprobe is unavailable),
+/// or `None` overall when it should be downloaded again.
+fn check_existing_output(
+    host: &dyn WorkerHost,
+    path: &Path,
+    job: &QueueItem,
+    expected_duration: Option<f64>,
+) -> Option<Option<IntegrityVerdict>> {
+    // An interrupted cross-device move leaves this marker next to the file.
+    if incomplete_marker_path(path).exists() {
+        return None;
+    }
+    let size = fs::metadata(path).map(|meta| meta.len()).ok()?;
+    if size == 0 {
+        return None;
+    }
+    match host.probe_media(path) {
+        Some(result) => match verdict_from_probe_result(&result, &job.mode, expected_duration) {
+            IntegrityVerdict::Verified => Some(Some(IntegrityVerdict::Verified)),
+            _ => None,
+        },
+        None => {
+            let large_enough = job
+                .estimated_bytes
+                .map(|estimated| size as f64 >= estimated as f64 * SKIP_MIN_SIZE_RATIO)
+                .unwrap_or(true);
+            large_enough.then_some(None)
+        }
     }
 }
 
-// Re-export resolve_downloaded_file_path for use in file_ops resolution within the worker.
-// (Already available via crate::file_ops — no re-export needed.)
+/// Marks a job completed with the file already on disk. Hooks do not run
+/// because nothing new was downloaded.
+fn complete_from_existing(
+    host: &dyn WorkerHost,
+    shared: &Arc<Mutex<AppState>>,
+    job_id: &str,
+    path: &Path,
+    verdict: Option<IntegrityVerdict>,
+) {
+    let mut state = lock_or_recover(shared, "worker_thread/collision_skip");
+    let update = state
+        .queue
+        .iter_mut()
+        .find(|item| item.id == job_id && item.status == "downloading")
+        .map(|item| {
+            let verify_line = apply_integrity_verdict(item, verdict);
+            item.progress_percent = 100.0;
+            item.speed_text = None;
+            item.eta_text = None;
+            item.error_category = None;
+            item.output_path = Some(path.to_string_lossy().to_string());
+            item.output_bytes = fs::metadata(path).ok().map(|meta| meta.len());
+            let lines: Vec<String> = [
+                format!("[skip] {} already exists", path.display()),
+                verify_line,
+            ]
+            .into_iter()
+            .filter(|line| append_download_log(item, line))
+            .collect();
+            JobUpdate::from_item(item, lines)
+        });
+    let saved = host.persist_queue(&state, PersistMode::Immediate);
+    drop(state);
+    saved.wait();
+    if let Some(update) = update {
+        host.emit_job(update);
+    }
+}
+
+/// Fails a job whose output file exists under the `fail` collision policy.
+fn fail_output_conflict(
+    host: &dyn WorkerHost,
+    shared: &Arc<Mutex<AppState>>,
+    job_id: &str,
+    path: &Path,
+) {
+    let message = format!("같은 이름의 파일이 이미 있습니다: {}", path.display());
+    let mut state = lock_or_recover(shared, "worker_thread/collision_fail");
+    let update = state
+        .queue
+        .iter_mut()
+        .find(|item| item.id == job_id && item.status == "downloading")
+        .map(|item| {
+            item.status = "failed".to_string();
+            item.speed_text = None;
+            item.eta_text = None;
+            item.error_message = Some(message.clone());
+            let lines = append_download_log(item, &message)
+                .then(|| message.clone())
+                .into_iter()
+                .collect();
+            JobUpdate::from_item(item, lines)
+        });
+    let saved = host.persist_queue(&state, PersistMode::Immediate);
+    drop(state);
+    saved.wait();
+    if let Some(update) = update {
+        host.emit_job(update);
+    }
+}
+
+// ============================================================================
+// Output finalization
+// ============================================================================
+
+const INTERRUPTED_LOG_LINE: &str = "[shutdown] interrupted; partial download kept";
+
+/// Requeues a job stopped by app shutdown so the next launch resumes it from
+/// the partial data left in its temp dir.
+fn mark_interrupted(item: &mut QueueItem) {
+    item.status = "queued".to_string();
+    item.speed_text = None;
+    item.eta_text = None;
+}
+
+fn requeue_interrupted_job(
+    host: &dyn WorkerHost,
+    shared: &Arc<Mutex<AppState>>,
+    throttle: &JobUpdateThrottle,
+    job_id: &str,
+) {
+    let mut state = lock_or_recover(shared, "worker_thread/shutdown_requeue");
+    let update = state
+        .queue
+        .iter_mut()
+        .find(|item| item.id == job_id && item.status != "paused" && item.status != "canceled")
+        .and_then(|item| {
+            mark_interrupted(item);
+            let appended = append_download_log(item, INTERRUPTED_LOG_LINE)
+                .then(|| INTERRUPTED_LOG_LINE.to_string())
+                .into_iter()
+                .collect();
+            throttle.record(item, appended, true)
+        });
+    let saved = host.persist_queue(&state, PersistMode::Immediate);
+    drop(state);
+    saved.wait();
+    if let Some(update) = update {
+        host.emit_job(update);
+    }
+}
+
+fn is_shutting_down(runtime: &Arc<Mutex<RuntimeState>>) -> bool {
+    lock_or_recover(runtime, "worker_thread/shutdown_check").shutting_down
+}
+
+fn job_status_is(shared: &Arc<Mutex<AppState>>, job_id: &str, status: &str) -> bool {
+    let state = lock_or_recover(shared, "worker_thread/status_check");
+    state
+        .queue
+        .iter()
+        .any(|item| item.id == job_id && item.status == status)
+}
+
+/// Returns true when the job was paused, canceled or removed.
+fn job_stopped(shared: &Arc<Mutex<AppState>>, job_id: &str) -> bool {
+    let state = lock_or_recover(shared, "worker_thread/stop_check");
+    state
+        .queue
+        .iter()
+        .find(|item| item.id == job_id)
+        .map(|item| item.status == "paused" || item.status == "canceled")
+        .unwrap_or(true)
+}
+
+/// Moves the finished download into place and probes it. The verdict is
+/// `None` when ffprobe is not available.
+///
+/// A copy to another volume reports `move_progress_percent` on the job,
+/// once per whole percent.
+fn finalize_output(
+    host: &dyn WorkerHost,
+    shared: &Arc<Mutex<AppState>>,
+    throttle: &JobUpdateThrottle,
+    temp_dir: &Path,
+    final_output_path: &Path,
+    mode: &crate::metadata::DownloadMode,
+    expected_duration: Option<f64>,
+) -> Result<Option<IntegrityVerdict>, String> {
+    let completed_path = resolve_downloaded_file_path(temp_dir, expected_extension(mode))?;
+    let move_options = MoveOptions {
+        verify_checksum: lock_or_recover(shared, "worker_thread/move_options")
+            .settings
+            .verify_move_checksum,
+    };
+    let mut last_percent: Option<u64> = None;
+    let mut report_progress = |copied: u64, total: u64| {
+        let percent = if total == 0 {
+            100.0
+        } else {
+            copied as f64 * 100.0 / total as f64
+        };
+        if last_percent == Some(percent as u64) {
+            return;
+        }
+        last_percent = Some(percent as u64);
+        let update = {
+            let mut state = lock_or_recover(shared, "worker_thread/move_progress");
+            state
+                .queue
+                .iter_mut()
+                .find(|item| item.id == throttle.job_id)
+                .and_then(|item| {
+                    item.move_progress_percent = Some(percent);
+                    throttle.record(item, Vec::new(), false)
+                })
+        };
+        if let Some(update) = update {
+            host.emit_job(update);
+        }
+    };
+    move_file_with_progress(
+        &completed_path,
+        final_output_path,
+        move_options,
+        &mut report_progress,
+    )?;
+    Ok(host
+        .probe_media(final_output_path)
+        .map(|result| verdict_from_probe_result(&result, mode, expected_duration)))
+}
+
+/// Sets the job's final status from the integrity verdict and returns the
+/// log line describing it. Broken outputs fail the job but stay on disk so
+/// the user can inspect or delete them.
+fn apply_integrity_verdict(item: &mut QueueItem, verdict: Option<IntegrityVerdict>) -> String {
+    item.integrity = verdict.as_ref().map(|verdict| verdict.label().to_string());
+    match verdict {
+        None => {
+            item.status = "completed".to_string();
+            item.error_message = None;
+            "[verify] skipped: ffprobe not found".to_string()
+        }
+        Some(IntegrityVerdict::Verified) => {
+            item.status = "completed".to_string();
+            item.error_message = None;
+            "[verify] ok".to_string()
+        }
+        Some(IntegrityVerdict::Suspect(reason)) => {
+            item.status = "completed".to_string();
+            item.error_message = Some(reason.clone());
+            format!("[verify] suspect: {reason}")
+        }
+        Some(IntegrityVerdict::Broken(reason)) => {
+            item.status = "failed".to_string();
+            item.error_message = Some(reason.clone());
+            format!("[verify] broken: {reason}")
+        }
+    }
+}
+
+// ============================================================================
+// Post-download hooks
+// ============================================================================
+
+/// Runs the configured hooks for a finished job and appends their output to
+/// the job log. Hooks run on the worker thread, outside the state lock.
+fn run_post_download_hooks(
+    host: &dyn WorkerHost,
+    shared: &Arc<Mutex<AppState>>,
+    throttle: &JobUpdateThrottle,
+    job: &QueueItem,
+) {
+    let hooks: Vec<PostDownloadHook> = {
+        let state = lock_or_recover(shared, "worker_thread/hooks_settings");
+        state
+            .settings
+            .post_download_hooks
+            .iter()
+            .filter(|hook| hook_applies(hook, &job.status))
+            .cloned()
+            .collect()
+    };
+
+    for hook in hooks {
+        let (program, args) = expand_hook_command(&hook, job);
+        let result = host.run_command(&program, &args, hook.timeout_sec * 1000);
+        let lines = hook_log_lines(&hook.command, &result);
+
+        let update = {
+            let mut state = lock_or_recover(shared, "worker_thread/hooks_log");
+            let update = state
+                .queue
+                .iter_mut()
+                .find(|item| item.id == job.id)
+                .map(|item| {
+                    let appended: Vec<String> = lines
+                        .into_iter()
+                        .filter(|line| append_download_log(item, line))
+                        .collect();
+                    throttle.record(item, appended, true)
+                });
+            host.persist_queue(&state, PersistMode::Deferred);
+            update.flatten()
+        };
+        if let Some(update) = update {
+            host.emit_job(update);
+        }
+    }
+}
@@ -1,7 +1,9 @@
 use dirs::download_dir;
+use serde::Serialize;
+use std::collections::HashSet;
 use std::env;
 use std::fs;
-use std::io::{Read, Write};
+use std::io::{Read, Seek, SeekFrom, Write};
 use std::path::{Path, PathBuf};
 use std::process::{Child, Command, Stdio};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
@@ -13,6 +15,10 @@ use std::os::windows::process::CommandExt;
 const TEMP_DOWNLOADS_DIR: &str = "tmp-downloads";
 const QUEUE_FILE: &str = "queue_state.json";
 const SETTINGS_FILE: &str = "settings.json";
+const MIGRATION_PLAN_FILE: &str = "download_migration.json";
+/// Hidden folder inside a download folder used as the staging area for
+/// `TempLocation::DestinationVolume`.
+pub const STAGING_DIR_NAME: &str = ".tubeextract-staging";
 const MANAGED_BIN_DIR: &str = "bin";
 
 /// Well-known directories to search for yt-dlp and ffmpeg on Windows.
@@ -44,6 +50,20 @@ pub fn app_data_dir(app: &AppHandle) -> PathBuf {
     path
 }
 
+/// Application data directory resolved before Tauri starts, matching
+/// `app_data_dir` for the bundle identifier. Used by the single-instance lock.
+pub fn startup_app_data_dir(identifier: &str) -> PathBuf {
+    let path = dirs::data_dir()
+        .map(|dir| dir.join(identifier))
+        .unwrap_or_else(|| {
+            download_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join("tubeextract-data")
+        });
+    let _ = fs::create_dir_all(&path);
+    path
+}
+
 /// Returns the root directory for temporary downloads.
 pub fn temp_downloads_root_dir(app: &AppHandle) -> PathBuf {
     app_data_dir(app).join(TEMP_DOWNLOADS_DIR)
@@ -54,6 +74,25 @@ pub fn temp_job_dir_path(app: &AppHandle, job_id: &str) -> PathBuf {
     temp_downloads_root_dir(app).join(job_id)
 }
 
+/// Returns the staging root inside `download_dir`.
+pub fn staging_root(download_dir: &Path) -> PathBuf {
+    download_dir.join(STAGING_DIR_NAME)
+}
+
+/// Returns the staging directory for a job, creating the hidden staging root
+/// inside `download_dir` if needed.
+pub fn prepare_staging_dir(download_dir: &Path, job_id: &str) -> PathBuf {
+    let root = staging_root(download_dir);
+    if !root.exists() && fs::create_dir_all(&root).is_ok() {
+        // Dot folders are already hidden elsewhere; Windows needs the attribute.
+        #[cfg(target_os = "windows")]
+        {
+            let _ = configure_hidden_process(Command::new("attrib").arg("+h").arg(&root)).status();
+        }
+    }
+    root.join(job_id)
+}
+
 /// Returns the path to the persisted queue file.
 pub fn queue_file_path(app: &AppHandle) -> PathBuf {
     app_data_dir(app).join(QUEUE_FILE)
@@ -64,6 +103,11 @@ pub fn settings_file_path(app: &AppHandle) -> PathBuf {
     app_data_dir(app).join(SETTINGS_FILE)
 }
 
+/// Returns the path of the pending download folder migration plan.
+pub fn migration_plan_path(app: &AppHandle) -> PathBuf {
+    app_data_dir(app).join(MIGRATION_PLAN_FILE)
+}
+
 /// Returns the directory where managed (downloaded) binaries are stored.
 pub fn managed_bin_dir_path(app: &AppHandle) -> PathBuf {
     app_data_dir(app).join(MANAGED_BIN_DIR)
@@ -321,40 +365,265 @@ pub fn run_command_capture(
 // Atomic file I/O
 // ============================================================================
 
+/// Number of timestamped backup generations kept next to the queue and settings files.
+pub const BACKUP_GENERATIONS: usize = 5;
+
+/// A new generation is started at most this often; saves in between refresh
+/// the newest one, so frequent queue writes do not push out older states.
+pub const BACKUP_ROTATION_INTERVAL_MS: u128 = 60_000;
+
 /// Atomically writes `content` to `path` using a temp-file + rename strategy.
-/// On POSIX systems the rename is atomic. On Windows it is near-atomic.
+/// The temp file is fsynced before the rename and the directory after it, so
+/// a power loss leaves either the old or the new content, never an empty file.
 // @MX:NOTE: [AUTO] Atomic write via temp-file + rename. POSIX atomic; near-atomic on Windows.
 pub fn write_atomic(path: &Path, content: &str) -> Result<(), String> {
     let tmp_path = PathBuf::from(format!("{}.tmp", path.display()));
-    fs::write(&tmp_path, content).map_err(|e| e.to_string())?;
+    let written = fs::File::create(&tmp_path)
+        .and_then(|mut file| {
+            file.write_all(content.as_bytes())?;
+            file.sync_all()
+        })
+        .map_err(|e| e.to_string());
+    if let Err(err) = written {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(err);
+    }
     fs::rename(&tmp_path, path).map_err(|e| {
         let _ = fs::remove_file(&tmp_path);
         e.to_string()
     })?;
+    sync_directory(path.parent().unwrap_or_else(|| Path::new(".")));
+    Ok(())
+}
+
+/// Backup files for `path`, newest first: the timestamped `<name>.bak.<millis>`
+/// generations, then a legacy single `<name>.bak` if one is left over.
+pub fn backup_generation_paths(path: &Path) -> Vec<PathBuf> {
+    let generations = timestamped_backups(path);
+    let mut paths: Vec<PathBuf> = generations.into_iter().map(|(_, path)| path).collect();
+    let legacy = PathBuf::from(format!("{}.bak", path.display()));
+    if legacy.is_file() {
+        paths.push(legacy);
+    }
+    paths
+}
+
+/// Stores `content` as the newest backup generation of `path` and prunes
+/// generations beyond `BACKUP_GENERATIONS`. Content identical to the newest
+/// generation is not written again, so restarts do not push out older backups.
+pub fn write_backup_generation(path: &Path, content: &str) -> Result<(), String> {
+    store_backup_generation(path, content, true)
+}
+
+/// Like `write_backup_generation`, but only writes when a new generation is
+/// due. Used for the queue, which is saved far more often than it needs a
+/// fresh backup.
+pub fn rotate_backup_generation(path: &Path, content: &str) -> Result<(), String> {
+    store_backup_generation(path, content, false)
+}
+
+fn store_backup_generation(path: &Path, content: &str, refresh_newest: bool) -> Result<(), String> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    let mut generations = timestamped_backups(path);
+    if let Some((_, newest)) = generations.first() {
+        if fs::read_to_string(newest).is_ok_and(|existing| existing == content) {
+            return Ok(());
+        }
+    }
+    let target = match generations.first() {
+        Some((stamp, newest)) if now.saturating_sub(*stamp) < BACKUP_ROTATION_INTERVAL_MS => {
+            if !refresh_newest {
+                return Ok(());
+            }
+            newest.clone()
+        }
+        _ => {
+            let created = PathBuf::from(format!("{}.bak.{now}", path.display()));
+            generations.insert(0, (now, created.clone()));
+            created
+        }
+    };
+    write_atomic(&target, content)?;
+    for (_, stale) in generations.iter().skip(BACKUP_GENERATIONS) {
+        let _ = fs::remove_file(stale);
+    }
     Ok(())
 }
 
+/// `(millis, path)` for every `<name>.bak.<millis>` next to `path`, newest first.
+fn timestamped_backups(path: &Path) -> Vec<(u128, PathBuf)> {
+    let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+        return Vec::new();
+    };
+    let prefix = format!("{file_name}.bak.");
+    let parent = path.parent().unwrap_or_else(|| Path::new("."));
+    let Ok(entries) = fs::read_dir(parent) else {
+        return Vec::new();
+    };
+    let mut generations: Vec<(u128, PathBuf)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_str()?.to_string();
+            let stamp = name.strip_prefix(&prefix)?.parse::<u128>().ok()?;
+            Some((stamp, entry.path()))
+        })
+        .collect();
+    generations.sort_by_key(|(stamp, _)| std::cmp::Reverse(*stamp));
+    generations
+}
+
 // ============================================================================
 // Directory / file helpers
 // ============================================================================
 
+/// Returns the free space available to this process on the volume holding
+/// `path`. Missing directories are resolved through their nearest existing
+/// ancestor, so the check works before the directory is created.
+pub fn available_space(path: &Path) -> Option<u64> {
+    let existing = path.ancestors().find(|candidate| candidate.exists())?;
+    fs2::statvfs(existing)
+        .ok()
+        .map(|stat| stat.available_space())
+}
+
 /// Silently removes a directory and all its contents, ignoring errors.
 pub fn remove_directory_safe(path: &Path) {
     let _ = fs::remove_dir_all(path);
 }
 
-/// Moves a file atomically.
-/// Same-FS: uses fs::rename (atomic). Cross-device: writes .incomplete marker,
-/// copies, verifies size, removes marker, removes source.
+/// What a temp cleanup removed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TempCleanupReport {
+    pub removed_entries: usize,
+    pub reclaimed_bytes: u64,
+}
+
+impl TempCleanupReport {
+    /// Adds the counts of `other` to this report.
+    pub fn merge(&mut self, other: TempCleanupReport) {
+        self.removed_entries += other.removed_entries;
+        self.reclaimed_bytes = self.reclaimed_bytes.saturating_add(other.reclaimed_bytes);
+    }
+}
+
+/// Removes entries under a temp root that no job will resume. Job directories
+/// named in `keep` hold resumable partial downloads and are never touched;
+/// anything else is removed once nothing inside it changed for `max_age`.
+/// An emptied root is removed as well.
+pub fn clean_temp_job_dirs(
+    root: &Path,
+    keep: &HashSet<String>,
+    max_age: Duration,
+    now: SystemTime,
+) -> TempCleanupReport {
+    let mut report = TempCleanupReport::default();
+    let Ok(entries) = fs::read_dir(root) else {
+        return report;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        if path.is_dir() && keep.contains(&name) {
+            continue;
+        }
+        let age = last_modified(&path)
+            .and_then(|modified| now.duration_since(modified).ok())
+            .unwrap_or_default();
+        if age < max_age {
+            continue;
+        }
+        let bytes = if path.is_dir() {
+            crate::diagnostics::calculate_directory_size(&path)
+        } else {
+            entry.metadata().map(|meta| meta.len()).unwrap_or(0)
+        };
+        let removed = if path.is_dir() {
+            fs::remove_dir_all(&path).is_ok()
+        } else {
+            fs::remove_file(&path).is_ok()
+        };
+        if removed {
+            report.removed_entries += 1;
+            report.reclaimed_bytes = report.reclaimed_bytes.saturating_add(bytes);
+        }
+    }
+    // Only succeeds when nothing is left.
+    let _ = fs::remove_dir(root);
+    report
+}
+
+/// Newest modification time of `path` or anything below it.
+fn last_modified(path: &Path) -> Option<SystemTime> {
+    let own = fs::metadata(path).and_then(|meta| meta.modified()).ok();
+    if !path.is_dir() {
+        return own;
+    }
+    fs::read_dir(path)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| last_modified(&entry.path()))
+        .chain(own)
+        .max()
+}
+
+/// Bytes read and written per step of a cross-device copy.
+const MOVE_CHUNK_BYTES: usize = 1024 * 1024;
+/// A cross-device copy is flushed and its marker updated after this many
+/// bytes, so a resumed move only repeats the unsynced tail.
+const MOVE_CHECKPOINT_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Options for `move_file_with_progress`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MoveOptions {
+    /// Compare CRC32 checksums of source and copy after a cross-device copy.
+    pub verify_checksum: bool,
+}
+
+/// Path of the marker that exists while `destination` is being copied.
+pub fn incomplete_marker_path(destination: &Path) -> PathBuf {
+    PathBuf::from(format!("{}.incomplete", destination.display()))
+}
+
+/// Source recorded in the `.incomplete` marker next to `destination`.
+pub fn incomplete_move_source(destination: &Path) -> Option<PathBuf> {
+    let content = fs::read_to_string(incomplete_marker_path(destination)).ok()?;
+    let marker: serde_json::Value = serde_json::from_str(&content).ok()?;
+    marker
+        .get("source")
+        .and_then(serde_json::Value::as_str)
+        .map(PathBuf::from)
+}
+
+/// Moves a file with the default options and no progress reporting.
+pub fn move_file_atomic(source: &Path, destination: &Path) -> Result<(), String> {
+    move_file_with_progress(source, destination, MoveOptions::default(), &mut |_, _| {})
+}
+
+/// Moves a file, reporting `(copied, total)` bytes while a cross-device copy runs.
+/// Same-FS: uses fs::rename (atomic). Cross-device: see `copy_file_across_devices`.
 // @MX:WARN: [AUTO] Cross-device copy is NOT atomic. Incomplete marker guards against power loss corruption.
 // @MX:REASON: [AUTO] See SPEC-STABILITY-002 REQ-002 for incomplete marker protocol.
-pub fn move_file_atomic(source: &Path, destination: &Path) -> Result<(), String> {
+pub fn move_file_with_progress(
+    source: &Path,
+    destination: &Path,
+    options: MoveOptions,
+    on_progress: &mut dyn FnMut(u64, u64),
+) -> Result<(), String> {
     if let Some(parent) = destination.parent() {
         fs::create_dir_all(parent).map_err(|err| err.to_string())?;
     }
 
     match fs::rename(source, destination) {
-        Ok(()) => Ok(()),
+        Ok(()) => {
+            // The rename replaced any partial copy left by an earlier attempt.
+            let _ = fs::remove_file(incomplete_marker_path(destination));
+            Ok(())
+        }
         Err(err) => {
             let is_cross_device = err.kind() == std::io::ErrorKind::CrossesDevices
                 || err.raw_os_error() == Some(18)
@@ -362,48 +631,174 @@ pub fn move_file_atomic(source: &Path, destination: &Path) -> Result<(), String>
             if !is_cross_device {
                 return Err(err.to_string());
             }
-            // Cross-device: use .incomplete marker for crash safety
-            let incomplete_path = PathBuf::from(format!("{}.incomplete", destination.display()));
-            // Write marker (content is minimal JSON for diagnostics)
-            let started_at = SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_secs();
-            let marker_content = serde_json::json!({
-                "started_at": started_at,
-                "source": source.display().to_string()
-            });
-            {
-                let mut f = fs::File::create(&incomplete_path)
-                    .map_err(|e| format!("Failed to create .incomplete marker: {e}"))?;
-                f.write_all(marker_content.to_string().as_bytes())
-                    .map_err(|e| e.to_string())?;
-            }
+            copy_file_across_devices(source, destination, options, on_progress)
+        }
+    }
+}
 
-            // Copy source → destination
-            let copied_bytes = fs::copy(source, destination).map_err(|copy_err| {
-                // Leave .incomplete marker so startup scan can detect it
-                format!("Copy failed: {copy_err}")
-            })?;
-
-            // Verify size matches
-            let source_size = fs::metadata(source).map(|m| m.len()).unwrap_or(0);
-            if source_size > 0 && copied_bytes != source_size {
-                return Err(format!(
-                    "Size mismatch after copy: expected {source_size} bytes, got {copied_bytes}"
-                ));
-            }
+/// Copies `source` to `destination` in chunks, then removes the source.
+///
+/// An `.incomplete` marker records the source and the bytes known to be on
+/// disk. When a marker for the same source exists, the copy resumes from its
+/// last checkpoint. The file and its directory are fsynced before the marker
+/// is removed; on failure the marker stays so the startup scan can find it.
+pub fn copy_file_across_devices(
+    source: &Path,
+    destination: &Path,
+    options: MoveOptions,
+    on_progress: &mut dyn FnMut(u64, u64),
+) -> Result<(), String> {
+    let marker_path = incomplete_marker_path(destination);
+    let total = fs::metadata(source)
+        .map(|meta| meta.len())
+        .map_err(|err| format!("Copy failed: {err}"))?;
+    let resume_from = resume_offset(&marker_path, source, destination, total);
+    let started_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    write_incomplete_marker(&marker_path, source, started_at, total, resume_from)?;
+
+    let mut reader = fs::File::open(source).map_err(|err| format!("Copy failed: {err}"))?;
+    let mut writer = fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(destination)
+        .map_err(|err| format!("Copy failed: {err}"))?;
+    // Anything past the last checkpoint may not have reached the disk.
+    writer
+        .set_len(resume_from)
+        .and_then(|_| writer.seek(SeekFrom::Start(resume_from)))
+        .and_then(|_| reader.seek(SeekFrom::Start(resume_from)))
+        .map_err(|err| format!("Copy failed: {err}"))?;
+
+    let mut buffer = vec![0u8; MOVE_CHUNK_BYTES];
+    let mut copied = resume_from;
+    let mut unsynced: u64 = 0;
+    on_progress(copied, total);
+    loop {
+        let read = reader
+            .read(&mut buffer)
+            .map_err(|err| format!("Copy failed: {err}"))?;
+        if read == 0 {
+            break;
+        }
+        writer
+            .write_all(&buffer[..read])
+            .map_err(|err| format!("Copy failed: {err}"))?;
+        copied += read as u64;
+        unsynced += read as u64;
+        if unsynced >= MOVE_CHECKPOINT_BYTES {
+            writer
+                .sync_data()
+                .map_err(|err| format!("Copy failed: {err}"))?;
+            write_incomplete_marker(&marker_path, source, started_at, total, copied)?;
+            unsynced = 0;
+        }
+        on_progress(copied, total);
+    }
+    writer
+        .sync_all()
+        .map_err(|err| format!("Copy failed: {err}"))?;
+    drop(writer);
+
+    if copied != total {
+        return Err(format!(
+            "Size mismatch after copy: expected {total} bytes, got {copied}"
+        ));
+    }
+    if options.verify_checksum && file_crc32(source)? != file_crc32(destination)? {
+        // Start over on the next attempt rather than resuming a bad copy.
+        let _ = fs::remove_file(destination);
+        let _ = fs::remove_file(&marker_path);
+        return Err("Checksum mismatch after copy".to_string());
+    }
 
-            // Remove .incomplete marker (transfer complete)
-            let _ = fs::remove_file(&incomplete_path);
+    let parent = destination.parent().unwrap_or_else(|| Path::new("."));
+    sync_directory(parent);
+    let _ = fs::remove_file(&marker_path);
+    sync_directory(parent);
 
-            // Remove source
-            fs::remove_file(source).map_err(|remove_err| remove_err.to_string())?;
-            Ok(())
+    fs::remove_file(source).map_err(|remove_err| remove_err.to_string())?;
+    Ok(())
+}
+
+/// Writes the marker describing an in-progress copy (minimal JSON).
+fn write_incomplete_marker(
+    marker_path: &Path,
+    source: &Path,
+    started_at: u64,
+    total_bytes: u64,
+    synced_bytes: u64,
+) -> Result<(), String> {
+    let marker_content = serde_json::json!({
+        "started_at": started_at,
+        "source": source.display().to_string(),
+        "total_bytes": total_bytes,
+        "synced_bytes": synced_bytes,
+    });
+    let mut f = fs::File::create(marker_path)
+        .map_err(|e| format!("Failed to create .incomplete marker: {e}"))?;
+    f.write_all(marker_content.to_string().as_bytes())
+        .and_then(|_| f.sync_all())
+        .map_err(|e| e.to_string())
+}
+
+/// Offset a copy can resume from: the marker's last checkpoint, if it was
+/// written for the same source and the destination still holds that much.
+fn resume_offset(marker_path: &Path, source: &Path, destination: &Path, total: u64) -> u64 {
+    let Some(marker) = fs::read_to_string(marker_path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+    else {
+        return 0;
+    };
+    let same_source = marker.get("source").and_then(serde_json::Value::as_str)
+        == Some(source.display().to_string().as_str());
+    let same_total = marker
+        .get("total_bytes")
+        .and_then(serde_json::Value::as_u64)
+        == Some(total);
+    let synced = marker
+        .get("synced_bytes")
+        .and_then(serde_json::Value::as_u64)
+        .unwrap_or(0);
+    let on_disk = fs::metadata(destination)
+        .map(|meta| meta.len())
+        .unwrap_or(0);
+    if same_source && same_total && synced <= total && synced <= on_disk {
+        synced
+    } else {
+        0
+    }
+}
+
+/// CRC32 of a file's contents.
+fn file_crc32(path: &Path) -> Result<u32, String> {
+    let mut file = fs::File::open(path).map_err(|err| err.to_string())?;
+    let mut hasher = crc32fast::Hasher::new();
+    let mut buffer = vec![0u8; MOVE_CHUNK_BYTES];
+    loop {
+        let read = file.read(&mut buffer).map_err(|err| err.to_string())?;
+        if read == 0 {
+            return Ok(hasher.finalize());
         }
+        hasher.update(&buffer[..read]);
     }
 }
 
+/// Flushes a directory's entries so created or removed files survive a
+/// power loss. Windows has no directory handles to sync; NTFS journals them.
+pub(crate) fn sync_directory(dir: &Path) {
+    #[cfg(not(target_os = "windows"))]
+    if let Ok(handle) = fs::File::open(dir) {
+        let _ = handle.sync_all();
+    }
+    #[cfg(target_os = "windows")]
+    let _ = dir;
+}
+
 /// Resolves the path of a downloaded media file in a temporary directory.
 ///
 /// Prefers `media.<ext>` if it exists; otherwise returns the most recently
@@ -442,7 +837,7 @@ pub fn resolve_downloaded_file_path(
         return Err("완성 파일을 임시 폴더에서 찾지 못했습니다.".to_string());
     }
 
-    candidates.sort_by(|a, b| b.1.cmp(&a.1));
+    candidates.sort_by_key(|c| std::cmp::Reverse(c.1));
     Ok(candidates[0].0.clone())
 }
 
@@ -469,3 +864,15 @@ pub fn normalize_download_dir(raw_path: &str) -> String {
     }
     raw.to_string()
 }
+
+/// Normalizes a destination folder like `normalize_download_dir`, creates it
+/// if needed and checks that it can be written to.
+pub fn validate_output_dir(raw_path: &str) -> Result<String, String> {
+    let normalized = normalize_download_dir(raw_path);
+    let dir = Path::new(&normalized);
+    let _ = fs::create_dir_all(dir);
+    if !crate::diagnostics::can_write_to_dir(dir) {
+        return Err(format!("저장 폴더에 쓸 수 없습니다: {normalized}"));
+    }
+    Ok(normalized)
+}
@@ -0,0 +1,143 @@
+// @MX:NOTE: User-defined post-download hooks. Commands run without a shell: each argument is
+// expanded separately, so titles or URLs containing quotes or `;` cannot inject commands.
+
+use crate::file_ops::CommandCaptureResult;
+use crate::queue::QueueItem;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// Variables that may appear in a hook command or argument.
+pub const HOOK_VARIABLES: &[&str] = &["output_path", "title", "url", "status", "channel"];
+
+const DEFAULT_HOOK_TIMEOUT_SEC: u64 = 120;
+const MAX_HOOK_TIMEOUT_SEC: u64 = 3600;
+/// Output lines kept per hook run in the job log.
+const MAX_HOOK_LOG_LINES: usize = 20;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Which job outcomes trigger a hook.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum HookTrigger {
+    #[default]
+    Completed,
+    Failed,
+    Always,
+}
+
+/// A command run after a job completes or fails.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PostDownloadHook {
+    /// Program to run (path or name on PATH).
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub run_on: HookTrigger,
+    #[serde(default = "default_hook_timeout_sec")]
+    pub timeout_sec: u64,
+}
+
+fn default_hook_timeout_sec() -> u64 {
+    DEFAULT_HOOK_TIMEOUT_SEC
+}
+
+// ============================================================================
+// Hook helpers
+// ============================================================================
+
+/// Drops hooks without a command and clamps timeouts to 1..=3600 seconds.
+pub fn normalize_post_download_hooks(hooks: Vec<PostDownloadHook>) -> Vec<PostDownloadHook> {
+    hooks
+        .into_iter()
+        .filter_map(|hook| {
+            let command = hook.command.trim().to_string();
+            if command.is_empty() {
+                return None;
+            }
+            Some(PostDownloadHook {
+                command,
+                timeout_sec: hook.timeout_sec.clamp(1, MAX_HOOK_TIMEOUT_SEC),
+                ..hook
+            })
+        })
+        .collect()
+}
+
+/// Returns true when `hook` should run for a job that ended with `status`.
+pub fn hook_applies(hook: &PostDownloadHook, status: &str) -> bool {
+    match hook.run_on {
+        HookTrigger::Completed => status == "completed",
+        HookTrigger::Failed => status == "failed",
+        HookTrigger::Always => status == "completed" || status == "failed",
+    }
+}
+
+/// Replaces `{output_path}`, `{title}`, `{url}`, `{status}` and `{channel}`
+/// with values from `job` in a single pass, so a title that contains
+/// `{channel}` is not expanded again. Unknown placeholders are left untouched.
+pub fn expand_hook_template(template: &str, job: &QueueItem) -> String {
+    let placeholder = Regex::new(r"\{(\w+)\}").unwrap_or_else(|_| unreachable!());
+    placeholder
+        .replace_all(template, |caps: &regex::Captures| {
+            if HOOK_VARIABLES.contains(&&caps[1]) {
+                hook_value(&caps[1], job)
+            } else {
+                caps[0].to_string()
+            }
+        })
+        .into_owned()
+}
+
+fn hook_value(variable: &str, job: &QueueItem) -> String {
+    match variable {
+        "output_path" => job.output_path.clone().unwrap_or_default(),
+        "title" => job.title.clone(),
+        "url" => job.url.clone(),
+        "status" => job.status.clone(),
+        "channel" => job.channel.clone().unwrap_or_default(),
+        _ => String::new(),
+    }
+}
+
+/// Expands the hook's command and arguments for `job`.
+pub fn expand_hook_command(hook: &PostDownloadHook, job: &QueueItem) -> (String, Vec<String>) {
+    (
+        expand_hook_template(&hook.command, job),
+        hook.args
+            .iter()
+            .map(|arg| expand_hook_template(arg, job))
+            .collect(),
+    )
+}
+
+/// Summarizes a hook run as job log lines: a status line followed by the
+/// last few lines of its output.
+pub fn hook_log_lines(command: &str, result: &CommandCaptureResult) -> Vec<String> {
+    let status = if result.timed_out {
+        "timed out".to_string()
+    } else {
+        format!("exit {}", result.code)
+    };
+    let output: Vec<String> = result
+        .stdout
+        .lines()
+        .chain(result.stderr.lines())
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| format!("[hook] {line}"))
+        .collect();
+    let skip = output.len().saturating_sub(MAX_HOOK_LOG_LINES);
+
+    let mut lines = vec![format!("[hook] {command}: {status}")];
+    lines.extend(output.into_iter().skip(skip));
+    lines
+}
@@ -0,0 +1,203 @@
+// @MX:NOTE: Single-instance guard. The first process holds an exclusive lock on a file in
+// the app data directory for its whole lifetime and listens on a loopback port. A later
+// launch finds the lock taken, hands its arguments to that port and exits before it can
+// touch the queue or settings files.
+
+use crate::file_ops::write_atomic;
+use fs2::FileExt;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+const INSTANCE_LOCK_FILE: &str = "instance.lock";
+/// Kept apart from the lock file because Windows blocks reads of locked ranges.
+const INSTANCE_ENDPOINT_FILE: &str = "instance.endpoint.json";
+const FORWARD_TIMEOUT_MS: u64 = 2_000;
+const FORWARD_RETRY_INTERVAL_MS: u64 = 100;
+
+/// Bundle identifier from `tauri.conf.json`. The lock is taken before Tauri
+/// starts, so the data directory is resolved from it directly.
+pub const APP_IDENTIFIER: &str = "com.tubeextract.desktop";
+
+/// Event carrying `ForwardedArgs` to the frontend of the running instance.
+pub const FORWARDED_ARGS_EVENT: &str = "instance-args-forwarded";
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Where the running instance accepts forwarded arguments.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InstanceEndpoint {
+    pub port: u16,
+    /// Shared secret so other local processes cannot inject arguments.
+    pub token: String,
+}
+
+/// Arguments handed over by a second launch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForwardedArgs {
+    pub args: Vec<String>,
+    /// The `http(s)` URLs among `args`.
+    pub urls: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ForwardMessage {
+    token: String,
+    args: Vec<String>,
+}
+
+/// Held by the primary instance. Dropping it releases the lock.
+pub struct InstanceGuard {
+    _lock: File,
+    listener: TcpListener,
+    token: String,
+}
+
+/// Result of trying to become the primary instance.
+pub enum InstanceClaim {
+    Primary(InstanceGuard),
+    /// Another process holds the lock.
+    Secondary,
+}
+
+// ============================================================================
+// Claiming the lock
+// ============================================================================
+
+/// Takes the exclusive instance lock in `data_dir` and publishes the endpoint
+/// for later launches.
+pub fn claim_instance(data_dir: &Path) -> io::Result<InstanceClaim> {
+    fs::create_dir_all(data_dir)?;
+    let lock = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(data_dir.join(INSTANCE_LOCK_FILE))?;
+    if lock.try_lock_exclusive().is_err() {
+        return Ok(InstanceClaim::Secondary);
+    }
+
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+    let endpoint = InstanceEndpoint {
+        port: listener.local_addr()?.port(),
+        token: uuid::Uuid::new_v4().to_string(),
+    };
+    let serialized = serde_json::to_string(&endpoint).map_err(io::Error::other)?;
+    write_atomic(&data_dir.join(INSTANCE_ENDPOINT_FILE), &serialized).map_err(io::Error::other)?;
+
+    Ok(InstanceClaim::Primary(InstanceGuard {
+        _lock: lock,
+        listener,
+        token: endpoint.token,
+    }))
+}
+
+/// The `http(s)` URLs among launch arguments; the program path is skipped.
+pub fn launch_urls(args: &[String]) -> Vec<String> {
+    args.iter()
+        .skip(1)
+        .map(|arg| arg.trim())
+        .filter(|arg| arg.starts_with("https://") || arg.starts_with("http://"))
+        .map(str::to_string)
+        .collect()
+}
+
+// ============================================================================
+// Forwarding arguments
+// ============================================================================
+
+/// Sends `args` to the instance holding the lock in `data_dir`. The endpoint
+/// file may be missing or left over from an earlier run while that instance
+/// is still starting, so reading and sending are retried until the timeout.
+pub fn forward_to_primary(data_dir: &Path, args: &[String]) -> io::Result<()> {
+    let deadline = Instant::now() + Duration::from_millis(FORWARD_TIMEOUT_MS);
+    loop {
+        let result = fs::read_to_string(data_dir.join(INSTANCE_ENDPOINT_FILE))
+            .and_then(|content| {
+                serde_json::from_str::<InstanceEndpoint>(&content).map_err(io::Error::other)
+            })
+            .and_then(|endpoint| forward_args(&endpoint, args));
+        match result {
+            Ok(()) => return Ok(()),
+            Err(err) if Instant::now() >= deadline => return Err(err),
+            Err(_) => std::thread::sleep(Duration::from_millis(FORWARD_RETRY_INTERVAL_MS)),
+        }
+    }
+}
+
+/// Sends `args` to the instance listening at `endpoint`.
+pub fn forward_args(endpoint: &InstanceEndpoint, args: &[String]) -> io::Result<()> {
+    let timeout = Duration::from_millis(FORWARD_TIMEOUT_MS);
+    let address = SocketAddr::from((Ipv4Addr::LOCALHOST, endpoint.port));
+    let mut stream = TcpStream::connect_timeout(&address, timeout)?;
+    stream.set_write_timeout(Some(timeout))?;
+    let message = ForwardMessage {
+        token: endpoint.token.clone(),
+        args: args.to_vec(),
+    };
+    let mut line = serde_json::to_string(&message).map_err(io::Error::other)?;
+    line.push('\n');
+    stream.write_all(line.as_bytes())?;
+    stream.flush()
+}
+
+impl InstanceGuard {
+    /// Accepts forwarded arguments for the rest of the process lifetime,
+    /// calling `on_args` for each launch. The guard, and so the lock, lives
+    /// on the listener thread.
+    pub fn listen(self, on_args: impl Fn(ForwardedArgs) + Send + 'static) {
+        std::thread::spawn(move || {
+            for stream in self.listener.incoming().flatten() {
+                if let Some(args) = read_forwarded_args(stream, &self.token) {
+                    on_args(args);
+                }
+            }
+        });
+    }
+
+    /// Serves forwarded launches for the app: focuses the main window and
+    /// emits `FORWARDED_ARGS_EVENT`.
+    pub fn serve(self, app: AppHandle) {
+        self.listen(move |forwarded| {
+            eprintln!(
+                "[INSTANCE] Second launch forwarded {} URL(s)",
+                forwarded.urls.len()
+            );
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.unminimize();
+                let _ = window.show();
+                let _ = window.set_focus();
+            }
+            let _ = app.emit(FORWARDED_ARGS_EVENT, forwarded);
+        });
+    }
+}
+
+fn read_forwarded_args(stream: TcpStream, token: &str) -> Option<ForwardedArgs> {
+    stream
+        .set_read_timeout(Some(Duration::from_millis(FORWARD_TIMEOUT_MS)))
+        .ok()?;
+    let mut line = String::new();
+    BufReader::new(stream).read_line(&mut line).ok()?;
+    let message = serde_json::from_str::<ForwardMessage>(&line).ok()?;
+    if message.token != token {
+        eprintln!("[INSTANCE] Ignored forwarded arguments with a wrong token");
+        return None;
+    }
+    Some(ForwardedArgs {
+        urls: launch_urls(&message.args),
+        args: message.args,
+    })
+}
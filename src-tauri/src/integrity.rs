@@ -0,0 +1,172 @@
+// @MX:NOTE: Post-download integrity checks. ffprobe output for the moved file is compared with
+// what the job asked for, so truncated or stream-less merges are not reported as completed.
+
+use crate::file_ops::CommandCaptureResult;
+use crate::metadata::DownloadMode;
+use serde_json::Value;
+use std::path::Path;
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// Absolute duration difference always accepted, in seconds.
+pub const DURATION_TOLERANCE_SEC: f64 = 3.0;
+/// Relative duration difference accepted for long media.
+pub const DURATION_TOLERANCE_RATIO: f64 = 0.02;
+/// Outputs shorter than this fraction of the expected duration are treated as truncated.
+pub const TRUNCATED_DURATION_RATIO: f64 = 0.9;
+
+/// ffprobe is killed if it has not finished within this many milliseconds.
+pub const FFPROBE_TIMEOUT_MS: u64 = 30_000;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Stream layout and duration reported by ffprobe.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MediaProbe {
+    /// True when a real video stream is present (cover art is not counted).
+    pub has_video: bool,
+    pub has_audio: bool,
+    pub duration_sec: Option<f64>,
+}
+
+/// Outcome of checking a downloaded file.
+#[derive(Debug, Clone, PartialEq)]
+pub enum IntegrityVerdict {
+    Verified,
+    /// Playable, but the duration does not match the analysis.
+    Suspect(String),
+    /// Unreadable, missing an expected stream, or truncated.
+    Broken(String),
+}
+
+impl IntegrityVerdict {
+    /// Value stored in `QueueItem::integrity`.
+    pub fn label(&self) -> &'static str {
+        match self {
+            IntegrityVerdict::Verified => "verified",
+            IntegrityVerdict::Suspect(_) => "suspect",
+            IntegrityVerdict::Broken(_) => "broken",
+        }
+    }
+}
+
+// ============================================================================
+// ffprobe
+// ============================================================================
+
+/// Arguments that make ffprobe print stream types and container duration as JSON.
+pub fn ffprobe_args(path: &Path) -> Vec<String> {
+    vec![
+        "-v".to_string(),
+        "error".to_string(),
+        "-show_entries".to_string(),
+        "stream=codec_type:stream_disposition=attached_pic:format=duration".to_string(),
+        "-of".to_string(),
+        "json".to_string(),
+        path.to_string_lossy().to_string(),
+    ]
+}
+
+/// Parses the JSON printed by ffprobe with `ffprobe_args`.
+pub fn parse_ffprobe_output(stdout: &str) -> Result<MediaProbe, String> {
+    let json: Value =
+        serde_json::from_str(stdout).map_err(|err| format!("ffprobe 출력 파싱 실패: {err}"))?;
+
+    let mut probe = MediaProbe {
+        has_video: false,
+        has_audio: false,
+        duration_sec: json
+            .pointer("/format/duration")
+            .and_then(|value| match value {
+                Value::String(text) => text.parse::<f64>().ok(),
+                other => other.as_f64(),
+            })
+            .filter(|duration| duration.is_finite() && *duration > 0.0),
+    };
+
+    for stream in json
+        .get("streams")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        let attached_pic = stream
+            .pointer("/disposition/attached_pic")
+            .and_then(Value::as_i64)
+            .unwrap_or(0)
+            == 1;
+        match stream.get("codec_type").and_then(Value::as_str) {
+            Some("video") if !attached_pic => probe.has_video = true,
+            Some("audio") => probe.has_audio = true,
+            _ => {}
+        }
+    }
+
+    Ok(probe)
+}
+
+/// Compares a probe with the job's mode and the analyzed duration.
+///
+/// `expected_duration` should be `None` when the output is not expected to
+/// match the source length (e.g. SponsorBlock segments were cut out).
+pub fn check_media_integrity(
+    probe: &MediaProbe,
+    mode: &DownloadMode,
+    expected_duration: Option<f64>,
+) -> IntegrityVerdict {
+    if !probe.has_audio {
+        return IntegrityVerdict::Broken("출력 파일에 오디오 스트림이 없습니다.".to_string());
+    }
+    if *mode == DownloadMode::Video && !probe.has_video {
+        return IntegrityVerdict::Broken("출력 파일에 비디오 스트림이 없습니다.".to_string());
+    }
+
+    let Some(expected) = expected_duration.filter(|duration| *duration > 0.0) else {
+        return IntegrityVerdict::Verified;
+    };
+    let Some(actual) = probe.duration_sec else {
+        return IntegrityVerdict::Suspect("출력 파일의 길이를 확인할 수 없습니다.".to_string());
+    };
+
+    if actual < expected * TRUNCATED_DURATION_RATIO {
+        return IntegrityVerdict::Broken(format!(
+            "출력 파일이 잘린 것으로 보입니다 ({actual:.0}초 / 예상 {expected:.0}초)."
+        ));
+    }
+    let tolerance = DURATION_TOLERANCE_SEC.max(expected * DURATION_TOLERANCE_RATIO);
+    if (actual - expected).abs() > tolerance {
+        return IntegrityVerdict::Suspect(format!(
+            "출력 파일 길이가 예상과 다릅니다 ({actual:.0}초 / 예상 {expected:.0}초)."
+        ));
+    }
+    IntegrityVerdict::Verified
+}
+
+/// Turns a finished ffprobe run into a verdict. A failed or timed-out probe
+/// means ffprobe could not read the file, which is treated as broken.
+pub fn verdict_from_probe_result(
+    result: &CommandCaptureResult,
+    mode: &DownloadMode,
+    expected_duration: Option<f64>,
+) -> IntegrityVerdict {
+    if result.timed_out {
+        return IntegrityVerdict::Broken("ffprobe 검사 시간이 초과되었습니다.".to_string());
+    }
+    if result.code != 0 {
+        let reason = result
+            .stderr
+            .lines()
+            .map(str::trim)
+            .rfind(|line| !line.is_empty())
+            .unwrap_or("알 수 없는 오류");
+        return IntegrityVerdict::Broken(format!("출력 파일을 읽을 수 없습니다: {reason}"));
+    }
+    match parse_ffprobe_output(&result.stdout) {
+        Ok(probe) => check_media_integrity(&probe, mode, expected_duration),
+        Err(err) => IntegrityVerdict::Broken(err),
+    }
+}
@@ -1,8 +1,17 @@
+mod backend;
 mod dependencies;
 mod diagnostics;
 mod download;
 mod file_ops;
+mod hooks;
+mod instance;
+mod integrity;
+mod library;
 mod metadata;
+mod migration;
+mod naming;
+mod network;
+mod persistence;
 mod queue;
 mod settings;
 mod state;
@@ -10,20 +19,80 @@ mod types;
 mod utils;
 
 // Re-export symbols used by integration tests (stability_tests.rs).
-pub use crate::download::{classify_download_error, retry_delay_ms_for_strategy, RetryStrategy};
+pub use crate::backend::{
+    DownloadBackend, DownloadProcess, DownloadRequest, ScriptedAttempt, ScriptedBackend,
+};
+pub use crate::download::{
+    apply_retry_jitter, classify_download_error, cookie_args, embed_args, normalize_retry_policy,
+    retry_delay_ms_for_strategy, sponsorblock_args, EmbedOptions, RetryPolicy, RetryRule,
+    RetryStrategy,
+};
+pub use crate::download::{
+    build_download_args, clamp_worker_count, disk_space_shortfall, shutdown_worker_pool,
+    stalled_message, terminate_job_process, ActiveProcess, RuntimeState, StallWatchdog, WorkerHost,
+    WorkerPool, MAX_CONCURRENT_DOWNLOADS,
+};
+pub use crate::file_ops::{
+    backup_generation_paths, clean_temp_job_dirs, copy_file_across_devices, incomplete_marker_path,
+    move_file_atomic, validate_output_dir, write_backup_generation, CommandCaptureResult,
+    MoveOptions, TempCleanupReport, BACKUP_GENERATIONS, STAGING_DIR_NAME,
+};
+pub use crate::hooks::{
+    expand_hook_command, expand_hook_template, hook_applies, hook_log_lines,
+    normalize_post_download_hooks, HookTrigger, PostDownloadHook,
+};
+pub use crate::instance::{
+    claim_instance, forward_args, forward_to_primary, launch_urls, ForwardedArgs, InstanceClaim,
+    InstanceEndpoint,
+};
+pub use crate::integrity::{
+    check_media_integrity, ffprobe_args, parse_ffprobe_output, verdict_from_probe_result,
+    IntegrityVerdict, MediaProbe,
+};
+pub use crate::library::{
+    apply_reconcile_outcomes, find_renamed_output, reconcile_outcomes, ReconcileOutcome,
+    ReconcileSummary,
+};
+pub use crate::metadata::{find_fallback_format, DownloadMode, FormatProfile, FormatSubstitution};
+pub use crate::migration::{
+    migrate_file, migration_destination, plan_migration, MigrationPlan, MigrationStep,
+};
+pub use crate::naming::{
+    normalize_file_name_template, render_file_name_template, DEFAULT_FILE_NAME_TEMPLATE,
+};
+pub use crate::network::{normalize_proxy_url, proxy_args};
+pub use crate::persistence::{
+    write_queue_file, PersistAck, PersistMode, QueuePersister, QUEUE_FLUSH_DEBOUNCE_MS,
+};
+pub use crate::queue::{
+    job_download_dir, scan_incomplete_markers_in, JobUpdate, QueueItem, QueueSnapshot,
+};
+pub use crate::settings::TempLocation;
+pub use crate::settings::{
+    default_settings, first_valid_backup, normalize_max_concurrent_downloads, AppSettings, AppState,
+};
+pub use crate::settings::{CollisionPolicy, SponsorBlockMode};
+pub use crate::utils::{
+    estimate_format_bytes, format_bytes, parse_sponsorblock_segment_count, sanitize_file_name,
+    sanitize_file_name_for, FileNamePolicy, NameLengthLimit, TargetFilesystem, UnicodeForm,
+};
 
 use crate::dependencies::{
     default_dependency_status, emit_dependency_status, start_dependency_bootstrap_if_needed,
     DependencyBootstrapStatus, DependencyRuntimeState, SharedDependencyState,
 };
-use crate::download::{RuntimeState, SharedRuntime};
-use crate::file_ops::{remove_directory_safe, temp_downloads_root_dir};
-use crate::queue::emit_queue_updated;
-use crate::settings::{
-    default_settings, load_queue_with_recovery, load_settings_with_recovery, SharedState,
-};
+use crate::download::{start_worker_pool, SharedRuntime, SHUTDOWN_TIMEOUT_MS};
+use crate::file_ops::{queue_file_path, startup_app_data_dir};
+use crate::instance::APP_IDENTIFIER;
+use crate::library::run_library_reconcile;
+use crate::migration::resume_pending_migration;
+use crate::persistence::SharedPersister;
+use crate::queue::{clean_temp_roots, emit_queue_updated, temp_cleanup_targets};
+use crate::settings::{load_queue_with_recovery, load_settings_with_recovery, SharedState};
+use crate::state::lock_or_recover;
 use crate::types::CommandResult;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use tauri::Manager;
 
 // ============================================================================
@@ -47,20 +116,55 @@ async fn get_dependency_bootstrap_status(
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    let builder = tauri::Builder::default()
-        .setup(|app| {
-            remove_directory_safe(&temp_downloads_root_dir(app.handle()));
+    // Only one process may own the queue and settings files.
+    let data_dir = startup_app_data_dir(APP_IDENTIFIER);
+    let launch_args: Vec<String> = std::env::args().collect();
+    let instance = match claim_instance(&data_dir) {
+        Ok(InstanceClaim::Primary(guard)) => Some(guard),
+        Ok(InstanceClaim::Secondary) => {
+            match forward_to_primary(&data_dir, &launch_args) {
+                Ok(()) => eprintln!("[INSTANCE] Forwarded launch to the running instance"),
+                Err(err) => eprintln!("[INSTANCE] Running instance did not respond: {err}"),
+            }
+            return;
+        }
+        Err(err) => {
+            eprintln!("[STABILITY] Instance lock unavailable, starting unguarded: {err}");
+            None
+        }
+    };
 
+    let builder = tauri::Builder::default()
+        .setup(move |app| {
+            if let Some(guard) = instance {
+                guard.serve(app.handle().clone());
+            }
             let mut initial_state = crate::settings::AppState {
                 queue: Vec::new(),
                 settings: default_settings(),
                 active_worker_count: 0,
+                queue_pause_reason: None,
             };
             load_settings_with_recovery(app.handle(), &mut initial_state);
             load_queue_with_recovery(app.handle(), &mut initial_state);
             crate::queue::scan_incomplete_markers(app.handle(), &mut initial_state);
-            app.manage(SharedState(Arc::new(Mutex::new(initial_state))));
-            app.manage(SharedRuntime(Arc::new(Mutex::new(RuntimeState::default()))));
+            // Keep partial downloads of jobs that will resume; drop old orphans.
+            let (temp_roots, resumable, max_age) =
+                temp_cleanup_targets(app.handle(), &initial_state);
+            let cleanup = clean_temp_roots(&temp_roots, &resumable, max_age);
+            eprintln!(
+                "[STABILITY] Temp cleanup: removed {} entries, reclaimed {}",
+                cleanup.removed_entries,
+                format_bytes(cleanup.reclaimed_bytes)
+            );
+            let shared_state = Arc::new(Mutex::new(initial_state));
+            let runtime = Arc::new(Mutex::new(RuntimeState::default()));
+            app.manage(SharedState(shared_state.clone()));
+            app.manage(SharedRuntime(runtime.clone()));
+            app.manage(SharedPersister(Arc::new(QueuePersister::start(
+                queue_file_path(app.handle()),
+                Duration::from_millis(QUEUE_FLUSH_DEBOUNCE_MS),
+            ))));
             let dependency_state = Arc::new(Mutex::new(DependencyRuntimeState {
                 status: default_dependency_status(),
                 dependencies_ready: false,
@@ -83,6 +187,22 @@ pub fn run() {
                 },
             );
             start_dependency_bootstrap_if_needed(app.handle().clone(), dependency_state);
+            resume_pending_migration(app.handle(), &shared_state);
+            let reconcile_on_startup = lock_or_recover(&shared_state, "setup/reconcile")
+                .settings
+                .reconcile_on_startup;
+            if reconcile_on_startup {
+                let reconcile_app = app.handle().clone();
+                let reconcile_state = shared_state.clone();
+                std::thread::spawn(move || {
+                    let summary = run_library_reconcile(&reconcile_app, &reconcile_state);
+                    eprintln!(
+                        "[LIBRARY] Startup reconcile: {} checked, {} relinked, {} missing",
+                        summary.checked, summary.relinked, summary.missing
+                    );
+                });
+            }
+            start_worker_pool(app.handle().clone(), shared_state, runtime);
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
@@ -91,9 +211,14 @@ pub fn run() {
             queue::enqueue_job,
             queue::pause_job,
             queue::resume_job,
+            queue::redownload_job,
+            queue::resume_queue,
             queue::cancel_job,
             queue::clear_terminal_jobs,
             queue::get_queue_snapshot,
+            queue::clean_temp_downloads,
+            library::reconcile_library,
+            migration::migrate_downloads,
             settings::get_settings,
             get_dependency_bootstrap_status,
             settings::pick_download_dir,
@@ -101,6 +226,7 @@ pub fn run() {
             diagnostics::run_diagnostics,
             diagnostics::check_update,
             diagnostics::get_storage_stats,
+            network::test_proxy,
             queue::delete_file,
             diagnostics::open_folder,
             diagnostics::open_external_url,
@@ -108,18 +234,27 @@ pub fn run() {
         .on_window_event(|window, event| {
             if let tauri::WindowEvent::Destroyed = event {
                 let app = window.app_handle().clone();
-                if let Some(runtime) = app.try_state::<SharedRuntime>() {
-                    if let Ok(mut rt) = runtime.0.lock() {
-                        let txs: Vec<_> = rt.shutdown_txs.drain(..).collect();
-                        for tx in txs {
-                            let _ = tx.send(());
-                        }
-                        eprintln!(
-                            "[STABILITY] Sent shutdown signal to {} worker thread(s)",
-                            rt.worker_handles.len()
-                        );
+                if let (Some(state), Some(runtime)) = (
+                    app.try_state::<SharedState>(),
+                    app.try_state::<SharedRuntime>(),
+                ) {
+                    let stopped = shutdown_worker_pool(
+                        &state.0,
+                        &runtime.0,
+                        Duration::from_millis(SHUTDOWN_TIMEOUT_MS),
+                    );
+                    eprintln!("[STABILITY] Worker pool shut down (clean: {stopped})");
+                    // Persist the requeued jobs even if a worker is still busy.
+                    let queue = state.0.lock().ok().map(|locked| locked.queue.clone());
+                    if let (Some(queue), Some(persister)) =
+                        (queue, app.try_state::<SharedPersister>())
+                    {
+                        persister.0.persist(queue, PersistMode::Immediate);
                     }
                 }
+                if let Some(persister) = app.try_state::<SharedPersister>() {
+                    persister.0.shutdown();
+                }
             }
         });
 
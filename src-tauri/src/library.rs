@@ -0,0 +1,229 @@
+// @MX:NOTE: Library reconciliation. Completed jobs keep their output path forever, so this
+// pass re-checks the files, follows renames within the same folder by size and duration,
+// and marks the rest `missing` so the UI can offer a re-download.
+
+use crate::file_ops::{resolve_executable, run_command_capture};
+use crate::integrity::{ffprobe_args, parse_ffprobe_output, FFPROBE_TIMEOUT_MS};
+use crate::persistence::PersistMode;
+use crate::queue::{emit_queue_updated, persist_queue, QueueItem};
+use crate::settings::{AppState, SharedState};
+use crate::state::lock_or_recover;
+use crate::types::CommandResult;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, State};
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// Largest duration difference, in seconds, accepted when matching a renamed file.
+pub const RENAME_DURATION_TOLERANCE_SEC: f64 = 2.0;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// What the reconcile pass found for one library item.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReconcileOutcome {
+    /// The recorded output file still exists.
+    Present,
+    /// The file was renamed; this is its new path.
+    Relinked(PathBuf),
+    /// Neither the file nor a likely rename was found.
+    Missing,
+}
+
+/// Counts reported to the frontend after a reconcile pass.
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconcileSummary {
+    pub checked: usize,
+    pub relinked: usize,
+    /// Items whose file came back since they were marked missing.
+    pub restored: usize,
+    /// Items that are missing after the pass.
+    pub missing: usize,
+}
+
+// ============================================================================
+// Reconcile pass
+// ============================================================================
+
+/// Completed or missing items that have an output path to check.
+pub fn is_library_item(item: &QueueItem) -> bool {
+    (item.status == "completed" || item.status == "missing") && item.output_path.is_some()
+}
+
+/// Checks every library item in `items`. `probe_duration` returns a file's
+/// duration, or `None` when it cannot be probed.
+pub fn reconcile_outcomes(
+    items: &[QueueItem],
+    probe_duration: &dyn Fn(&Path) -> Option<f64>,
+) -> Vec<(String, ReconcileOutcome)> {
+    // Files already recorded for some job are never taken for a rename.
+    let mut claimed: HashSet<PathBuf> = items
+        .iter()
+        .filter_map(|item| item.output_path.as_ref().map(PathBuf::from))
+        .collect();
+    items
+        .iter()
+        .filter(|item| is_library_item(item))
+        .map(|item| {
+            let path = PathBuf::from(item.output_path.as_deref().unwrap_or_default());
+            let outcome = if path.is_file() {
+                ReconcileOutcome::Present
+            } else if let Some(renamed) = find_renamed_output(item, &claimed, probe_duration) {
+                claimed.insert(renamed.clone());
+                ReconcileOutcome::Relinked(renamed)
+            } else {
+                ReconcileOutcome::Missing
+            };
+            (item.id.clone(), outcome)
+        })
+        .collect()
+}
+
+/// Looks for the renamed output of `item` next to its recorded path: a file
+/// with the same extension and exact size, confirmed by duration when the
+/// job's duration is known and the candidate can be probed. Only a single,
+/// unambiguous match is returned.
+pub fn find_renamed_output(
+    item: &QueueItem,
+    claimed: &HashSet<PathBuf>,
+    probe_duration: &dyn Fn(&Path) -> Option<f64>,
+) -> Option<PathBuf> {
+    let recorded = PathBuf::from(item.output_path.as_deref()?);
+    let size = item.output_bytes?;
+    let extension = recorded.extension()?.to_ascii_lowercase();
+    let entries = fs::read_dir(recorded.parent()?).ok()?;
+
+    let candidates: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            !claimed.contains(path)
+                && path.extension().map(|ext| ext.to_ascii_lowercase()) == Some(extension.clone())
+                && fs::metadata(path)
+                    .map(|meta| meta.is_file() && meta.len() == size)
+                    .unwrap_or(false)
+        })
+        .collect();
+
+    let matches: Vec<PathBuf> = match item.duration_sec {
+        Some(expected) => candidates
+            .into_iter()
+            .filter(|path| {
+                probe_duration(path)
+                    .map(|actual| (actual - expected).abs() <= RENAME_DURATION_TOLERANCE_SEC)
+                    .unwrap_or(true)
+            })
+            .collect(),
+        None => candidates,
+    };
+    match matches.as_slice() {
+        [single] => Some(single.clone()),
+        _ => None,
+    }
+}
+
+/// Applies `outcomes` to the queue. Items that changed status since the pass
+/// started are left alone. Returns the summary and whether anything changed.
+pub fn apply_reconcile_outcomes(
+    queue: &mut [QueueItem],
+    outcomes: &[(String, ReconcileOutcome)],
+) -> (ReconcileSummary, bool) {
+    let mut summary = ReconcileSummary::default();
+    let mut changed = false;
+    for (id, outcome) in outcomes {
+        let Some(item) = queue
+            .iter_mut()
+            .find(|item| &item.id == id && is_library_item(item))
+        else {
+            continue;
+        };
+        summary.checked += 1;
+        match outcome {
+            ReconcileOutcome::Present => {
+                if item.status == "missing" {
+                    item.status = "completed".to_string();
+                    item.error_message = None;
+                    summary.restored += 1;
+                    changed = true;
+                }
+            }
+            ReconcileOutcome::Relinked(path) => {
+                item.output_path = Some(path.to_string_lossy().to_string());
+                item.status = "completed".to_string();
+                item.error_message = None;
+                summary.relinked += 1;
+                changed = true;
+            }
+            ReconcileOutcome::Missing => {
+                if item.status != "missing" {
+                    item.status = "missing".to_string();
+                    item.error_message = Some(format!(
+                        "파일을 찾을 수 없습니다: {}",
+                        item.output_path.as_deref().unwrap_or_default()
+                    ));
+                    changed = true;
+                }
+                summary.missing += 1;
+            }
+        }
+    }
+    (summary, changed)
+}
+
+/// Runs a full reconcile pass. Files are checked outside the state lock; the
+/// results are applied, emitted and persisted afterwards.
+pub fn run_library_reconcile(app: &AppHandle, shared: &Arc<Mutex<AppState>>) -> ReconcileSummary {
+    let items: Vec<QueueItem> = lock_or_recover(shared, "library/snapshot").queue.clone();
+
+    let ffprobe = resolve_executable(app, "ffprobe");
+    let probe_duration = |path: &Path| -> Option<f64> {
+        if !Path::new(&ffprobe).exists() {
+            return None;
+        }
+        let args = ffprobe_args(path);
+        let arg_refs: Vec<&str> = args.iter().map(String::as_str).collect();
+        let result = run_command_capture(app, &ffprobe, &arg_refs, FFPROBE_TIMEOUT_MS);
+        if result.code != 0 {
+            return None;
+        }
+        parse_ffprobe_output(&result.stdout)
+            .ok()
+            .and_then(|probe| probe.duration_sec)
+    };
+    let outcomes = reconcile_outcomes(&items, &probe_duration);
+
+    let mut state = lock_or_recover(shared, "library/apply");
+    let (summary, changed) = apply_reconcile_outcomes(&mut state.queue, &outcomes);
+    if changed {
+        emit_queue_updated(app, &state);
+        let saved = persist_queue(app, &state, PersistMode::Immediate);
+        drop(state);
+        saved.wait();
+    }
+    summary
+}
+
+// ============================================================================
+// Tauri commands
+// ============================================================================
+
+/// Checks every completed job's output file and updates the queue.
+#[tauri::command]
+pub async fn reconcile_library(
+    app: AppHandle,
+    state: State<'_, SharedState>,
+) -> CommandResult<ReconcileSummary> {
+    let shared = state.0.clone();
+    tauri::async_runtime::spawn_blocking(move || run_library_reconcile(&app, &shared))
+        .await
+        .map_err(|err| err.to_string())
+}
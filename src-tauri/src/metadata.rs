@@ -1,7 +1,10 @@
 use crate::dependencies::{wait_for_dependencies, SharedDependencyState};
+use crate::download::{classify_download_error, cookie_args, needs_auth_message, RetryStrategy};
 use crate::file_ops::{resolve_executable, run_command_capture};
+use crate::network::proxy_args;
+use crate::settings::{normalize_optional_text, SharedState};
 use crate::types::CommandResult;
-use crate::utils::normalize_youtube_video_url;
+use crate::utils::{estimate_format_bytes, normalize_youtube_video_url};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashSet;
@@ -29,6 +32,37 @@ pub struct QualityOption {
     pub ext: String,
     #[serde(rename = "type")]
     pub mode: DownloadMode,
+    /// Expected download size in bytes, when yt-dlp reports or implies one.
+    #[serde(
+        rename = "estimatedBytes",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub estimated_bytes: Option<u64>,
+    /// Stream properties used to find an equivalent if the format disappears.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<FormatProfile>,
+}
+
+/// Stream properties of an analyzed format: height and video codec for
+/// video options, bitrate and audio codec for audio options.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FormatProfile {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub height: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub codec: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub abr: Option<f64>,
+}
+
+/// A replacement chosen for a format that is no longer offered.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormatSubstitution {
+    pub format_id: String,
+    /// Human-readable description, e.g. `720p avc1` or `128kbps opus`.
+    pub label: String,
 }
 
 /// Metadata and available quality options for a successfully analyzed URL.
@@ -46,6 +80,11 @@ pub struct AnalysisResult {
     pub video_options: Vec<QualityOption>,
     #[serde(rename = "audioOptions")]
     pub audio_options: Vec<QualityOption>,
+    #[serde(rename = "videoId", skip_serializing_if = "Option::is_none")]
+    pub video_id: Option<String>,
+    /// Upload date as `YYYYMMDD`.
+    #[serde(rename = "uploadDate", skip_serializing_if = "Option::is_none")]
+    pub upload_date: Option<String>,
 }
 
 // ============================================================================
@@ -53,17 +92,25 @@ pub struct AnalysisResult {
 // ============================================================================
 
 /// Runs yt-dlp with `-J` and returns the parsed JSON payload.
-fn fetch_metadata_json(app: &AppHandle, url: &str) -> Result<Value, String> {
+pub(crate) fn fetch_metadata_json(
+    app: &AppHandle,
+    url: &str,
+    extra_args: &[String],
+) -> Result<Value, String> {
     let yt_dlp = resolve_executable(app, "yt-dlp");
-    let output = run_command_capture(
-        app,
-        &yt_dlp,
-        &["--no-playlist", "-J", "--no-warnings", url],
-        ANALYZE_TIMEOUT_MS,
-    );
+    let mut args: Vec<&str> = vec!["--no-playlist", "-J", "--no-warnings"];
+    args.extend(extra_args.iter().map(String::as_str));
+    args.push(url);
+    let output = run_command_capture(app, &yt_dlp, &args, ANALYZE_TIMEOUT_MS);
 
     if output.code != 0 {
         let stderr = output.stderr.trim().to_string();
+        if matches!(
+            classify_download_error(&stderr),
+            RetryStrategy::NeedsAuth | RetryStrategy::BotCheck
+        ) {
+            return Err(needs_auth_message(&stderr));
+        }
         return Err(if stderr.is_empty() {
             "URL 분석에 실패했습니다.".to_string()
         } else {
@@ -82,6 +129,7 @@ fn parse_quality_options(json: &Value) -> (Vec<QualityOption>, Vec<QualityOption
         .cloned()
         .unwrap_or_default();
 
+    let duration_sec = json.get("duration").and_then(Value::as_f64).unwrap_or(0.0);
     let mut video_candidates: Vec<(i64, i64, QualityOption)> = Vec::new();
     let mut audio_candidates: Vec<(i64, QualityOption)> = Vec::new();
 
@@ -110,6 +158,7 @@ fn parse_quality_options(json: &Value) -> (Vec<QualityOption>, Vec<QualityOption
             .and_then(Value::as_str)
             .unwrap_or("none")
             .to_string();
+        let estimated_bytes = estimate_format_bytes(&format, duration_sec);
 
         if vcodec != "none" && height > 0 {
             let ext_priority = if ext == "mp4" {
@@ -128,6 +177,12 @@ fn parse_quality_options(json: &Value) -> (Vec<QualityOption>, Vec<QualityOption
                     label: format!("{height}p"),
                     ext: ext.clone(),
                     mode: DownloadMode::Video,
+                    estimated_bytes,
+                    profile: Some(FormatProfile {
+                        height: Some(height),
+                        codec: Some(vcodec.clone()),
+                        abr: None,
+                    }),
                 },
             ));
         }
@@ -141,13 +196,19 @@ fn parse_quality_options(json: &Value) -> (Vec<QualityOption>, Vec<QualityOption
                     label: format!("{abr_value}kbps"),
                     ext,
                     mode: DownloadMode::Audio,
+                    estimated_bytes,
+                    profile: Some(FormatProfile {
+                        height: None,
+                        codec: Some(acodec.clone()),
+                        abr: Some(abr),
+                    }),
                 },
             ));
         }
     }
 
     video_candidates.sort_by(|a, b| b.0.cmp(&a.0).then(b.1.cmp(&a.1)));
-    audio_candidates.sort_by(|a, b| b.0.cmp(&a.0));
+    audio_candidates.sort_by_key(|c| std::cmp::Reverse(c.0));
 
     // Deduplicate video options by height
     let mut seen_heights = HashSet::new();
@@ -170,6 +231,15 @@ fn parse_quality_options(json: &Value) -> (Vec<QualityOption>, Vec<QualityOption
         .map(|(_, option)| option)
         .collect();
 
+    // Video downloads merge in the best audio stream, so count it too.
+    let best_audio_bytes = audio_options
+        .first()
+        .and_then(|option| option.estimated_bytes)
+        .unwrap_or(0);
+    for option in &mut video_options {
+        option.estimated_bytes = option.estimated_bytes.map(|bytes| bytes + best_audio_bytes);
+    }
+
     (video_options, audio_options)
 }
 
@@ -186,6 +256,8 @@ fn build_analysis_result(
             label: "Best Video".to_string(),
             ext: "mp4".to_string(),
             mode: DownloadMode::Video,
+            estimated_bytes: None,
+            profile: None,
         }]
     } else {
         video_options
@@ -197,6 +269,8 @@ fn build_analysis_result(
             label: "Best Audio".to_string(),
             ext: "m4a".to_string(),
             mode: DownloadMode::Audio,
+            estimated_bytes: None,
+            profile: None,
         }]
     } else {
         audio_options
@@ -222,28 +296,180 @@ fn build_analysis_result(
             .to_string(),
         video_options,
         audio_options,
+        video_id: json.get("id").and_then(Value::as_str).map(str::to_string),
+        upload_date: json
+            .get("upload_date")
+            .and_then(Value::as_str)
+            .map(str::to_string),
     }
 }
 
+// ============================================================================
+// Format fallback
+// ============================================================================
+
+/// One entry of a fresh yt-dlp format list.
+struct FormatCandidate {
+    id: String,
+    height: i64,
+    codec: String,
+    abr: f64,
+    tbr: f64,
+}
+
+/// Codec family without profile details: `avc1.640028` -> `avc1`.
+fn codec_family(codec: &str) -> String {
+    codec.split('.').next().unwrap_or(codec).to_lowercase()
+}
+
+/// Reads the formats of `mode` from a yt-dlp JSON payload.
+fn format_candidates(json: &Value, mode: &DownloadMode) -> Vec<FormatCandidate> {
+    json.get("formats")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|format| {
+            let text = |key: &str| {
+                format
+                    .get(key)
+                    .and_then(Value::as_str)
+                    .unwrap_or("none")
+                    .to_string()
+            };
+            let vcodec = text("vcodec");
+            let acodec = text("acodec");
+            let height = format.get("height").and_then(Value::as_i64).unwrap_or(0);
+            let (matches, codec) = match mode {
+                DownloadMode::Video => (vcodec != "none" && height > 0, vcodec),
+                DownloadMode::Audio => (acodec != "none" && vcodec == "none", acodec),
+            };
+            matches.then(|| FormatCandidate {
+                id: text("format_id"),
+                height,
+                codec,
+                abr: format.get("abr").and_then(Value::as_f64).unwrap_or(0.0),
+                tbr: format.get("tbr").and_then(Value::as_f64).unwrap_or(0.0),
+            })
+        })
+        .collect()
+}
+
+/// Picks the nearest equivalent of an unavailable format from a fresh
+/// format list: same height (video) or bitrate (audio) and codec first, then
+/// the same height with any codec, then the next lower quality. Falls back to
+/// the lowest higher quality when nothing lower exists.
+pub fn find_fallback_format(
+    json: &Value,
+    mode: &DownloadMode,
+    unavailable_id: &str,
+    profile: Option<&FormatProfile>,
+) -> Option<FormatSubstitution> {
+    let candidates: Vec<FormatCandidate> = format_candidates(json, mode)
+        .into_iter()
+        .filter(|candidate| candidate.id != unavailable_id && !candidate.id.is_empty())
+        .collect();
+    let target_codec = profile
+        .and_then(|profile| profile.codec.as_deref())
+        .map(codec_family);
+    let same_codec = |candidate: &FormatCandidate| {
+        target_codec.as_deref() == Some(codec_family(&candidate.codec).as_str())
+    };
+    // Quality along the axis that matters for the mode.
+    let level = |candidate: &FormatCandidate| match mode {
+        DownloadMode::Video => candidate.height as f64,
+        DownloadMode::Audio => candidate.abr.floor(),
+    };
+    let target = profile
+        .and_then(|profile| match mode {
+            DownloadMode::Video => profile.height.map(|height| height as f64),
+            DownloadMode::Audio => profile.abr.map(f64::floor),
+        })
+        .unwrap_or(f64::MAX);
+    let rank = |a: &&FormatCandidate, b: &&FormatCandidate| {
+        level(a)
+            .total_cmp(&level(b))
+            .then(same_codec(a).cmp(&same_codec(b)))
+            .then(a.tbr.total_cmp(&b.tbr))
+    };
+
+    let chosen = candidates
+        .iter()
+        .filter(|candidate| level(candidate) == target && same_codec(candidate))
+        .max_by(rank)
+        .or_else(|| {
+            candidates
+                .iter()
+                .filter(|candidate| level(candidate) == target)
+                .max_by(rank)
+        })
+        .or_else(|| {
+            candidates
+                .iter()
+                .filter(|candidate| level(candidate) < target)
+                .max_by(rank)
+        })
+        .or_else(|| {
+            candidates.iter().min_by(|a, b| {
+                level(a)
+                    .total_cmp(&level(b))
+                    .then(same_codec(b).cmp(&same_codec(a)))
+                    .then(b.tbr.total_cmp(&a.tbr))
+            })
+        })?;
+
+    let codec = codec_family(&chosen.codec);
+    let label = match mode {
+        DownloadMode::Video => format!("{}p {codec}", chosen.height),
+        DownloadMode::Audio => format!("{}kbps {codec}", chosen.abr.floor() as i64),
+    };
+    Some(FormatSubstitution {
+        format_id: chosen.id.clone(),
+        label,
+    })
+}
+
 // ============================================================================
 // Tauri command
 // ============================================================================
 
 /// Fetches metadata for the given URL and returns available quality options.
+///
+/// `cookies_file` / `cookies_browser` override the cookie source from settings.
 #[tauri::command]
 pub async fn analyze_url(
     app: AppHandle,
+    state: State<'_, SharedState>,
     dependency: State<'_, SharedDependencyState>,
     url: String,
+    cookies_file: Option<String>,
+    cookies_browser: Option<String>,
 ) -> CommandResult<AnalysisResult> {
     let normalized_url = normalize_youtube_video_url(&url);
     if normalized_url.trim().is_empty() {
         return Err("URL is empty".to_string());
     }
 
+    let cookies_file = normalize_optional_text(cookies_file);
+    let cookies_browser = normalize_optional_text(cookies_browser);
+    let extra_args = {
+        let state = state
+            .0
+            .lock()
+            .map_err(|_| "state lock poisoned".to_string())?;
+        let cookies = if cookies_file.is_some() || cookies_browser.is_some() {
+            cookie_args(cookies_file.as_deref(), cookies_browser.as_deref())
+        } else {
+            cookie_args(
+                state.settings.cookies_file.as_deref(),
+                state.settings.cookies_browser.as_deref(),
+            )
+        };
+        [cookies, proxy_args(state.settings.proxy_url.as_deref())].concat()
+    };
+
     wait_for_dependencies(&app, &dependency.0)?;
 
-    let payload = fetch_metadata_json(&app, normalized_url.trim())?;
+    let payload = fetch_metadata_json(&app, normalized_url.trim(), &extra_args)?;
 
     // Reject live streams
     let is_live = payload
@@ -0,0 +1,295 @@
+// @MX:NOTE: Download folder migration. The plan is written before the first file moves and
+// shrinks as items finish, so an interrupted migration picks up on the next start. A file
+// whose cross-device copy was cut off resumes through its `.incomplete` marker. There is no
+// separate history store: the completed list in the UI is the queue itself, so rewriting a
+// queue item's `output_path` also updates the download history.
+
+use crate::file_ops::{
+    incomplete_move_source, migration_plan_path, move_file_with_progress, normalize_download_dir,
+    write_atomic, MoveOptions,
+};
+use crate::persistence::PersistMode;
+use crate::queue::{emit_job_updated, persist_queue, JobUpdate, QueueItem};
+use crate::settings::{AppState, SharedState};
+use crate::state::lock_or_recover;
+use crate::types::CommandResult;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, State};
+
+/// Event carrying a `MigrationProgress`.
+pub const MIGRATION_PROGRESS_EVENT: &str = "download-migration-progress";
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Persisted state of a running migration.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationPlan {
+    pub from_dir: String,
+    pub to_dir: String,
+    pub total: usize,
+    /// Ids of the jobs whose files have not been handled yet.
+    pub pending: Vec<String>,
+    /// Destination paths that already held another file.
+    #[serde(default)]
+    pub conflicts: Vec<String>,
+    /// `path: reason` for files that could not be moved.
+    #[serde(default)]
+    pub failed: Vec<String>,
+}
+
+/// Progress emitted while a migration runs.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationProgress {
+    pub total: usize,
+    pub done: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current_file: Option<String>,
+    /// Copy progress of `current_file` when it crosses volumes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_percent: Option<f64>,
+    pub conflicts: Vec<String>,
+    pub failed: Vec<String>,
+    pub finished: bool,
+}
+
+/// Result of migrating one file.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MigrationStep {
+    Moved(PathBuf),
+    /// The file was already moved before an interruption.
+    AlreadyMoved(PathBuf),
+    /// Another file occupies the destination; the source stays in place.
+    Conflict(PathBuf),
+    Failed(String),
+}
+
+impl MigrationPlan {
+    fn progress(
+        &self,
+        current_file: Option<String>,
+        file_percent: Option<f64>,
+    ) -> MigrationProgress {
+        MigrationProgress {
+            total: self.total,
+            done: self.total - self.pending.len(),
+            current_file,
+            file_percent,
+            conflicts: self.conflicts.clone(),
+            failed: self.failed.clone(),
+            finished: self.pending.is_empty(),
+        }
+    }
+}
+
+// ============================================================================
+// Planning
+// ============================================================================
+
+/// Where `output_path` goes when `from` moves to `to`, keeping any template
+/// subfolders. `None` when the file does not live under `from`.
+pub fn migration_destination(output_path: &Path, from: &Path, to: &Path) -> Option<PathBuf> {
+    output_path
+        .strip_prefix(from)
+        .ok()
+        .filter(|relative| !relative.as_os_str().is_empty())
+        .map(|relative| to.join(relative))
+}
+
+/// Plans moving every completed download stored under `from` to `to`.
+pub fn plan_migration(queue: &[QueueItem], from: &Path, to: &Path) -> MigrationPlan {
+    let pending: Vec<String> = queue
+        .iter()
+        .filter(|item| item.status == "completed")
+        .filter(|item| {
+            item.output_path
+                .as_deref()
+                .and_then(|path| migration_destination(Path::new(path), from, to))
+                .is_some()
+        })
+        .map(|item| item.id.clone())
+        .collect();
+    MigrationPlan {
+        from_dir: from.to_string_lossy().to_string(),
+        to_dir: to.to_string_lossy().to_string(),
+        total: pending.len(),
+        pending,
+        conflicts: Vec::new(),
+        failed: Vec::new(),
+    }
+}
+
+// ============================================================================
+// Moving files
+// ============================================================================
+
+/// Moves one file. A missing source whose destination already holds a file
+/// of `expected_bytes` was moved before an interruption.
+pub fn migrate_file(
+    source: &Path,
+    destination: &Path,
+    expected_bytes: Option<u64>,
+    on_progress: &mut dyn FnMut(u64, u64),
+) -> MigrationStep {
+    let resuming = incomplete_move_source(destination).as_deref() == Some(source);
+    if !source.exists() {
+        let moved = fs::metadata(destination)
+            .map(|meta| meta.is_file() && expected_bytes.unwrap_or(meta.len()) == meta.len())
+            .unwrap_or(false);
+        return if moved && !resuming {
+            MigrationStep::AlreadyMoved(destination.to_path_buf())
+        } else {
+            MigrationStep::Failed("원본 파일이 없습니다".to_string())
+        };
+    }
+    if destination.exists() && !resuming {
+        return MigrationStep::Conflict(destination.to_path_buf());
+    }
+    if let Some(parent) = destination.parent() {
+        if let Err(err) = fs::create_dir_all(parent) {
+            return MigrationStep::Failed(err.to_string());
+        }
+    }
+    match move_file_with_progress(source, destination, MoveOptions::default(), on_progress) {
+        Ok(()) => MigrationStep::Moved(destination.to_path_buf()),
+        Err(err) => MigrationStep::Failed(err),
+    }
+}
+
+/// Works through `plan`, saving it to `plan_path` after every file and
+/// removing it once the migration is finished.
+pub fn run_migration(app: &AppHandle, shared: &Arc<Mutex<AppState>>, plan_path: &Path) {
+    let Some(mut plan) = fs::read_to_string(plan_path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<MigrationPlan>(&content).ok())
+    else {
+        let _ = fs::remove_file(plan_path);
+        return;
+    };
+    let from = PathBuf::from(&plan.from_dir);
+    let to = PathBuf::from(&plan.to_dir);
+    let _ = app.emit(MIGRATION_PROGRESS_EVENT, plan.progress(None, None));
+
+    while let Some(id) = plan.pending.first().cloned() {
+        let job = lock_or_recover(shared, "migration/job")
+            .queue
+            .iter()
+            .find(|item| item.id == id && item.status == "completed")
+            .and_then(|item| {
+                Some((
+                    PathBuf::from(item.output_path.as_deref()?),
+                    item.output_bytes,
+                ))
+            });
+        let target = job.and_then(|(source, bytes)| {
+            migration_destination(&source, &from, &to)
+                .map(|destination| (source, destination, bytes))
+        });
+
+        if let Some((source, destination, bytes)) = target {
+            let current = source.to_string_lossy().to_string();
+            let _ = app.emit(
+                MIGRATION_PROGRESS_EVENT,
+                plan.progress(Some(current.clone()), None),
+            );
+            let mut last_percent: Option<u64> = None;
+            let step = migrate_file(&source, &destination, bytes, &mut |copied, total| {
+                let percent = if total == 0 {
+                    100.0
+                } else {
+                    copied as f64 * 100.0 / total as f64
+                };
+                if last_percent != Some(percent as u64) {
+                    last_percent = Some(percent as u64);
+                    let _ = app.emit(
+                        MIGRATION_PROGRESS_EVENT,
+                        plan.progress(Some(current.clone()), Some(percent)),
+                    );
+                }
+            });
+            match step {
+                MigrationStep::Moved(path) | MigrationStep::AlreadyMoved(path) => {
+                    let mut state = lock_or_recover(shared, "migration/update");
+                    if let Some(item) = state.queue.iter_mut().find(|item| item.id == id) {
+                        item.output_path = Some(path.to_string_lossy().to_string());
+                        emit_job_updated(app, JobUpdate::from_item(item, Vec::new()));
+                    }
+                    let saved = persist_queue(app, &state, PersistMode::Immediate);
+                    drop(state);
+                    saved.wait();
+                }
+                MigrationStep::Conflict(path) => {
+                    plan.conflicts.push(path.to_string_lossy().to_string());
+                }
+                MigrationStep::Failed(reason) => {
+                    plan.failed.push(format!("{current}: {reason}"));
+                }
+            }
+        }
+
+        plan.pending.remove(0);
+        if let Ok(serialized) = serde_json::to_string_pretty(&plan) {
+            let _ = write_atomic(plan_path, &serialized);
+        }
+    }
+
+    let _ = fs::remove_file(plan_path);
+    let _ = app.emit(MIGRATION_PROGRESS_EVENT, plan.progress(None, None));
+}
+
+/// Continues a migration left behind by a previous run, if any.
+pub fn resume_pending_migration(app: &AppHandle, shared: &Arc<Mutex<AppState>>) {
+    let plan_path = migration_plan_path(app);
+    if !plan_path.exists() {
+        return;
+    }
+    let app = app.clone();
+    let shared = shared.clone();
+    std::thread::spawn(move || run_migration(&app, &shared, &plan_path));
+}
+
+// ============================================================================
+// Tauri commands
+// ============================================================================
+
+/// Moves completed downloads stored under `from_dir` into the current
+/// download folder. Progress, including the first snapshot, is reported only
+/// through `MIGRATION_PROGRESS_EVENT` so it arrives in order.
+#[tauri::command]
+pub async fn migrate_downloads(
+    app: AppHandle,
+    state: State<'_, SharedState>,
+    from_dir: String,
+) -> CommandResult<()> {
+    let plan_path = migration_plan_path(&app);
+    let shared = state.0.clone();
+    {
+        let locked = shared
+            .lock()
+            .map_err(|_| "state lock poisoned".to_string())?;
+        if plan_path.exists() {
+            return Err("이미 다운로드 폴더를 옮기는 중입니다.".to_string());
+        }
+        let from = PathBuf::from(normalize_download_dir(&from_dir));
+        let to = PathBuf::from(&locked.settings.download_dir);
+        if from == to {
+            return Err("이전 폴더와 새 폴더가 같습니다.".to_string());
+        }
+        let plan = plan_migration(&locked.queue, &from, &to);
+        if plan.pending.is_empty() {
+            let _ = app.emit(MIGRATION_PROGRESS_EVENT, plan.progress(None, None));
+            return Ok(());
+        }
+        let serialized = serde_json::to_string_pretty(&plan).map_err(|err| err.to_string())?;
+        write_atomic(&plan_path, &serialized)?;
+    }
+
+    std::thread::spawn(move || run_migration(&app, &shared, &plan_path));
+    Ok(())
+}
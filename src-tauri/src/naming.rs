@@ -0,0 +1,166 @@
+// @MX:NOTE: Output file name templates. The template is split into path segments before
+// placeholders are expanded, so a `/` inside a title can never create a folder, and every
+// segment is sanitized on its own.
+
+use crate::metadata::DownloadMode;
+use crate::queue::QueueItem;
+use crate::utils::{normalize_youtube_video_url, sanitize_file_name_for, FileNamePolicy};
+use regex::Regex;
+use std::path::PathBuf;
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// Variables that may appear in a file name template.
+pub const FILE_NAME_TEMPLATE_VARIABLES: &[&str] = &[
+    "title",
+    "channel",
+    "upload_date",
+    "upload_year",
+    "id",
+    "quality",
+    "mode",
+    "playlist",
+    "playlist_index",
+];
+
+/// Template matching the original flat `<title>.<ext>` layout.
+pub const DEFAULT_FILE_NAME_TEMPLATE: &str = "{title}";
+
+const MAX_TEMPLATE_SEGMENTS: usize = 8;
+
+// ============================================================================
+// Template helpers
+// ============================================================================
+
+/// Trims the template, uses `/` as the only separator and drops empty, `.`
+/// and `..` segments. An empty result falls back to `{title}`.
+pub fn normalize_file_name_template(raw: &str) -> String {
+    let segments: Vec<&str> = raw
+        .split(['/', '\\'])
+        .map(str::trim)
+        .filter(|segment| !segment.is_empty() && *segment != "." && *segment != "..")
+        .take(MAX_TEMPLATE_SEGMENTS)
+        .collect();
+    if segments.is_empty() {
+        DEFAULT_FILE_NAME_TEMPLATE.to_string()
+    } else {
+        segments.join("/")
+    }
+}
+
+/// Value of one template variable for `job`; empty when the job lacks it.
+fn template_value(variable: &str, job: &QueueItem) -> String {
+    match variable {
+        "title" => job.title.clone(),
+        "channel" => job.channel.clone().unwrap_or_default(),
+        "upload_date" => job.upload_date.clone().unwrap_or_default(),
+        "upload_year" => job
+            .upload_date
+            .as_deref()
+            .filter(|date| date.len() >= 4 && date.is_char_boundary(4))
+            .map(|date| date[..4].to_string())
+            .unwrap_or_default(),
+        "id" => job
+            .video_id
+            .clone()
+            .or_else(|| youtube_video_id(&job.url))
+            .unwrap_or_default(),
+        "quality" => quality_label(job),
+        "mode" => match job.mode {
+            DownloadMode::Audio => "audio".to_string(),
+            DownloadMode::Video => "video".to_string(),
+        },
+        "playlist" => job.playlist.clone().unwrap_or_default(),
+        "playlist_index" => job
+            .playlist_index
+            .map(|index| format!("{index:02}"))
+            .unwrap_or_default(),
+        _ => String::new(),
+    }
+}
+
+/// `1080p` / `160kbps` from the format profile, else the raw format id.
+fn quality_label(job: &QueueItem) -> String {
+    let profile = job.format_profile.as_ref();
+    match job.mode {
+        DownloadMode::Video => profile
+            .and_then(|profile| profile.height)
+            .map(|height| format!("{height}p")),
+        DownloadMode::Audio => profile
+            .and_then(|profile| profile.abr)
+            .map(|abr| format!("{}kbps", abr.floor() as i64)),
+    }
+    .unwrap_or_else(|| job.quality_id.clone())
+}
+
+/// Video id from a YouTube URL, used when analysis did not report one.
+fn youtube_video_id(url: &str) -> Option<String> {
+    let normalized = normalize_youtube_video_url(url);
+    normalized
+        .split_once("watch?v=")
+        .map(|(_, id)| id.to_string())
+        .filter(|id| !id.is_empty())
+}
+
+/// Expands one template segment in a single left-to-right pass, so text from
+/// a title that looks like `{id}` is never expanded again. `[{var}]` and
+/// `({var})` disappear entirely when the variable is empty, so
+/// `{title} [{id}]` never leaves `[]` behind. Unknown names stay as typed.
+fn expand_segment(segment: &str, job: &QueueItem) -> String {
+    let placeholder =
+        Regex::new(r"\[\{(\w+)\}\]|\(\{(\w+)\}\)|\{(\w+)\}").unwrap_or_else(|_| unreachable!());
+    placeholder
+        .replace_all(segment, |caps: &regex::Captures| {
+            if let Some(variable) = caps.get(3) {
+                return if FILE_NAME_TEMPLATE_VARIABLES.contains(&variable.as_str()) {
+                    template_value(variable.as_str(), job)
+                } else {
+                    caps[0].to_string()
+                };
+            }
+            let (variable, open, close) = match caps.get(1) {
+                Some(variable) => (variable.as_str(), '[', ']'),
+                None => (caps.get(2).map_or("", |m| m.as_str()), '(', ')'),
+            };
+            if !FILE_NAME_TEMPLATE_VARIABLES.contains(&variable) {
+                return caps[0].to_string();
+            }
+            let value = template_value(variable, job);
+            if value.is_empty() {
+                String::new()
+            } else {
+                format!("{open}{value}{close}")
+            }
+        })
+        .into_owned()
+}
+
+/// Renders `template` for `job` as a relative folder path and a file stem
+/// (without extension), each segment sanitized for `policy`. Folder segments
+/// that expand to nothing are dropped; an empty file stem falls back to the title.
+pub fn render_file_name_template(
+    template: &str,
+    job: &QueueItem,
+    policy: &FileNamePolicy,
+) -> (PathBuf, String) {
+    let normalized = normalize_file_name_template(template);
+    let mut segments: Vec<&str> = normalized.split('/').collect();
+    let stem_template = segments.pop().unwrap_or(DEFAULT_FILE_NAME_TEMPLATE);
+
+    let folder = segments
+        .iter()
+        .map(|segment| expand_segment(segment, job))
+        .filter(|expanded| !expanded.trim().is_empty())
+        .map(|expanded| sanitize_file_name_for(&expanded, policy))
+        .collect::<PathBuf>();
+
+    let stem = expand_segment(stem_template, job);
+    let stem = if stem.trim().is_empty() {
+        sanitize_file_name_for(&job.title, policy)
+    } else {
+        sanitize_file_name_for(&stem, policy)
+    };
+    (folder, stem)
+}
@@ -0,0 +1,145 @@
+use serde::Serialize;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, State};
+use url::Url;
+
+use crate::settings::SharedState;
+use crate::state::lock_or_recover;
+use crate::types::CommandResult;
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+const PROXY_SCHEMES: &[&str] = &["http", "https", "socks4", "socks4a", "socks5", "socks5h"];
+const PROXY_TEST_URL: &str = "https://www.youtube.com/generate_204";
+const PROXY_TEST_TIMEOUT_MS: u64 = 10_000;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Result of the `test_proxy` command.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyTestResult {
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status_code: Option<u16>,
+    pub elapsed_ms: u64,
+    pub message: String,
+}
+
+// ============================================================================
+// Proxy helpers
+// ============================================================================
+
+/// Validates a raw proxy URL from settings.
+///
+/// Blank input means "no proxy" and returns `Ok(None)`. Anything else must be
+/// an absolute URL with an http, https or socks scheme and a host.
+pub fn normalize_proxy_url(raw: Option<&str>) -> Result<Option<String>, String> {
+    let Some(raw) = raw.map(str::trim).filter(|value| !value.is_empty()) else {
+        return Ok(None);
+    };
+    let parsed = Url::parse(raw).map_err(|_| format!("유효한 프록시 주소가 아닙니다: {raw}"))?;
+    let scheme = parsed.scheme().to_lowercase();
+    if !PROXY_SCHEMES.contains(&scheme.as_str()) {
+        return Err(format!("지원하지 않는 프록시 프로토콜입니다: {scheme}"));
+    }
+    if parsed.host_str().unwrap_or_default().is_empty() {
+        return Err(format!("프록시 호스트가 비어 있습니다: {raw}"));
+    }
+    Ok(Some(raw.to_string()))
+}
+
+/// Returns the yt-dlp flags that route a request through the proxy.
+pub fn proxy_args(proxy_url: Option<&str>) -> Vec<String> {
+    match proxy_url.map(str::trim).filter(|value| !value.is_empty()) {
+        Some(proxy) => vec!["--proxy".to_string(), proxy.to_string()],
+        None => Vec::new(),
+    }
+}
+
+/// Returns the proxy configured in settings, if the app state is available.
+pub fn current_proxy_url(app: &AppHandle) -> Option<String> {
+    let state = app.try_state::<SharedState>()?;
+    let locked = lock_or_recover(&state.0, "current_proxy_url");
+    locked.settings.proxy_url.clone()
+}
+
+/// Creates a blocking `reqwest` client builder that honours the proxy.
+pub fn blocking_client_builder(
+    proxy_url: Option<&str>,
+) -> Result<reqwest::blocking::ClientBuilder, String> {
+    let builder = reqwest::blocking::Client::builder();
+    match proxy_url {
+        Some(proxy) => {
+            let proxy = reqwest::Proxy::all(proxy).map_err(|err| err.to_string())?;
+            Ok(builder.proxy(proxy))
+        }
+        None => Ok(builder),
+    }
+}
+
+/// Creates an async `reqwest` client builder that honours the proxy.
+pub fn async_client_builder(proxy_url: Option<&str>) -> Result<reqwest::ClientBuilder, String> {
+    let builder = reqwest::Client::builder();
+    match proxy_url {
+        Some(proxy) => {
+            let proxy = reqwest::Proxy::all(proxy).map_err(|err| err.to_string())?;
+            Ok(builder.proxy(proxy))
+        }
+        None => Ok(builder),
+    }
+}
+
+// ============================================================================
+// Tauri commands
+// ============================================================================
+
+/// Sends a single request through the given proxy (or the saved one) and reports the outcome.
+#[tauri::command]
+pub async fn test_proxy(
+    state: State<'_, SharedState>,
+    proxy_url: Option<String>,
+) -> CommandResult<ProxyTestResult> {
+    let proxy_url = match proxy_url {
+        Some(raw) => normalize_proxy_url(Some(&raw))?,
+        None => {
+            let locked = state
+                .0
+                .lock()
+                .map_err(|_| "state lock poisoned".to_string())?;
+            locked.settings.proxy_url.clone()
+        }
+    };
+
+    let client = async_client_builder(proxy_url.as_deref())?
+        .timeout(Duration::from_millis(PROXY_TEST_TIMEOUT_MS))
+        .user_agent("TubeExtract")
+        .build()
+        .map_err(|err| err.to_string())?;
+
+    let started = Instant::now();
+    let result = client.get(PROXY_TEST_URL).send().await;
+    let elapsed_ms = started.elapsed().as_millis() as u64;
+
+    Ok(match result {
+        Ok(response) => {
+            let status = response.status();
+            ProxyTestResult {
+                ok: status.is_success(),
+                status_code: Some(status.as_u16()),
+                elapsed_ms,
+                message: format!("{PROXY_TEST_URL}: {status}"),
+            }
+        }
+        Err(err) => ProxyTestResult {
+            ok: false,
+            status_code: None,
+            elapsed_ms,
+            message: err.to_string(),
+        },
+    })
+}
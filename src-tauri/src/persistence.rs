@@ -0,0 +1,221 @@
+// @MX:NOTE: Write-behind queue persistence. Routine queue changes are coalesced and written
+// after a short debounce by a single actor thread; critical transitions bypass the debounce
+// and are on disk before the caller continues.
+
+use crate::file_ops::{rotate_backup_generation, write_atomic};
+use crate::queue::QueueItem;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// How long routine changes are held before being written.
+pub const QUEUE_FLUSH_DEBOUNCE_MS: u64 = 500;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// How urgently a queue change must reach disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PersistMode {
+    /// Coalesced with other changes and written after the debounce.
+    Deferred,
+    /// Written before `persist` returns: jobs added or removed, and jobs
+    /// reaching a paused or terminal state.
+    Immediate,
+}
+
+enum PersistMessage {
+    /// Latest queue contents; an ack sender makes the write immediate.
+    Write(Vec<QueueItem>, Option<Sender<()>>),
+    /// Writes any pending change, then acks.
+    Flush(Sender<()>),
+    Shutdown,
+}
+
+/// An immediate write handed to the actor. Submitting under the state lock
+/// keeps writes in order; `wait` then blocks until the write is on disk, so
+/// callers release the lock first. Dropping the ack waits as well.
+pub struct PersistAck(Option<Receiver<()>>);
+
+impl PersistAck {
+    /// An ack with nothing left to wait for.
+    pub fn written() -> Self {
+        Self(None)
+    }
+
+    /// Blocks until the submitted write is on disk.
+    pub fn wait(mut self) {
+        self.finish();
+    }
+
+    fn finish(&mut self) {
+        if let Some(ack) = self.0.take() {
+            let _ = ack.recv();
+        }
+    }
+}
+
+impl Drop for PersistAck {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
+
+/// Thread-safe wrapper managed as Tauri state.
+#[derive(Clone)]
+pub struct SharedPersister(pub Arc<QueuePersister>);
+
+/// Handle to the queue persistence actor.
+///
+/// After `shutdown`, writes fall back to synchronous file writes so late
+/// updates from draining workers are not lost.
+pub struct QueuePersister {
+    path: PathBuf,
+    tx: Mutex<Option<Sender<PersistMessage>>>,
+    handle: Mutex<Option<JoinHandle<()>>>,
+}
+
+// ============================================================================
+// Queue file writes
+// ============================================================================
+
+/// Writes the queue as pretty JSON. A backup generation is added only once
+/// per rotation interval, so most saves are a single write.
+pub fn write_queue_file(path: &Path, queue: &[QueueItem]) {
+    if let Ok(serialized) = serde_json::to_string_pretty(queue) {
+        if write_atomic(path, &serialized).is_ok() {
+            let _ = rotate_backup_generation(path, &serialized);
+        }
+    }
+}
+
+// ============================================================================
+// Persistence actor
+// ============================================================================
+
+impl QueuePersister {
+    /// Starts the actor thread that owns writes to `path`.
+    pub fn start(path: PathBuf, debounce: Duration) -> Self {
+        let (tx, rx) = channel::<PersistMessage>();
+        let actor_path = path.clone();
+        let handle = std::thread::spawn(move || run_actor(&actor_path, rx, debounce));
+        Self {
+            path,
+            tx: Mutex::new(Some(tx)),
+            handle: Mutex::new(Some(handle)),
+        }
+    }
+
+    /// Records the latest queue contents according to `mode` and waits for
+    /// immediate writes.
+    pub fn persist(&self, queue: Vec<QueueItem>, mode: PersistMode) {
+        self.submit(queue, mode).wait();
+    }
+
+    /// Records the latest queue contents without waiting. For immediate
+    /// writes, the returned ack completes once they are on disk.
+    pub fn submit(&self, queue: Vec<QueueItem>, mode: PersistMode) -> PersistAck {
+        let guard = self.tx.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(tx) = guard.as_ref() else {
+            drop(guard);
+            write_queue_file(&self.path, &queue);
+            return PersistAck::written();
+        };
+        match mode {
+            PersistMode::Deferred => {
+                let _ = tx.send(PersistMessage::Write(queue, None));
+                PersistAck::written()
+            }
+            PersistMode::Immediate => {
+                let (ack_tx, ack_rx) = channel::<()>();
+                let _ = tx.send(PersistMessage::Write(queue, Some(ack_tx)));
+                PersistAck(Some(ack_rx))
+            }
+        }
+    }
+
+    /// Blocks until every change recorded so far is on disk.
+    pub fn flush(&self) {
+        let ack = {
+            let guard = self.tx.lock().unwrap_or_else(|e| e.into_inner());
+            let Some(tx) = guard.as_ref() else {
+                return;
+            };
+            let (ack_tx, ack_rx) = channel::<()>();
+            let _ = tx.send(PersistMessage::Flush(ack_tx));
+            ack_rx
+        };
+        let _ = ack.recv();
+    }
+
+    /// Writes any pending change and stops the actor. Safe to call twice.
+    pub fn shutdown(&self) {
+        let tx = self.tx.lock().unwrap_or_else(|e| e.into_inner()).take();
+        if let Some(tx) = tx {
+            let _ = tx.send(PersistMessage::Shutdown);
+        }
+        let handle = self.handle.lock().unwrap_or_else(|e| e.into_inner()).take();
+        if let Some(handle) = handle {
+            let _ = handle.join();
+        }
+    }
+}
+
+fn run_actor(path: &Path, rx: Receiver<PersistMessage>, debounce: Duration) {
+    let mut pending: Option<Vec<QueueItem>> = None;
+    // Set by the first change after a write, so a steady stream of updates
+    // still reaches disk once per debounce window.
+    let mut deadline: Option<Instant> = None;
+
+    loop {
+        let message = match deadline {
+            Some(due) => match rx.recv_timeout(due.saturating_duration_since(Instant::now())) {
+                Ok(message) => Some(message),
+                Err(RecvTimeoutError::Timeout) => None,
+                Err(RecvTimeoutError::Disconnected) => break,
+            },
+            None => match rx.recv() {
+                Ok(message) => Some(message),
+                Err(_) => break,
+            },
+        };
+
+        match message {
+            None => {
+                if let Some(queue) = pending.take() {
+                    write_queue_file(path, &queue);
+                }
+                deadline = None;
+            }
+            Some(PersistMessage::Write(queue, None)) => {
+                pending = Some(queue);
+                deadline.get_or_insert_with(|| Instant::now() + debounce);
+            }
+            Some(PersistMessage::Write(queue, Some(ack))) => {
+                pending = None;
+                deadline = None;
+                write_queue_file(path, &queue);
+                let _ = ack.send(());
+            }
+            Some(PersistMessage::Flush(ack)) => {
+                if let Some(queue) = pending.take() {
+                    write_queue_file(path, &queue);
+                }
+                deadline = None;
+                let _ = ack.send(());
+            }
+            Some(PersistMessage::Shutdown) => break,
+        }
+    }
+
+    if let Some(queue) = pending.take() {
+        write_queue_file(path, &queue);
+    }
+}
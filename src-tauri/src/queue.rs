@@ -1,14 +1,23 @@
-use crate::download::{kill_active_child_unchecked, start_worker_if_needed, SharedRuntime};
-use crate::file_ops::{queue_file_path, write_atomic};
-use crate::metadata::DownloadMode;
-use crate::settings::{AppState, SharedState};
+use crate::download::{notify_worker_pool, terminate_job_process, RetryStrategy, SharedRuntime};
+use crate::file_ops::{
+    clean_temp_job_dirs, incomplete_move_source, queue_file_path, staging_root,
+    temp_downloads_root_dir, validate_output_dir, TempCleanupReport, STAGING_DIR_NAME,
+};
+use crate::metadata::{DownloadMode, FormatProfile};
+use crate::naming::render_file_name_template;
+use crate::persistence::{write_queue_file, PersistAck, PersistMode, SharedPersister};
+use crate::settings::{
+    normalize_optional_text, AppSettings, AppState, CollisionPolicy, SharedState, SponsorBlockMode,
+};
 use crate::types::CommandResult;
-use crate::utils::{normalize_youtube_video_url, sanitize_file_name};
+use crate::utils::{normalize_youtube_video_url, FileNamePolicy};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::HashSet;
 use std::fs;
-use std::path::PathBuf;
-use tauri::{AppHandle, Emitter, State};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Emitter, Manager, State};
 use uuid::Uuid;
 
 const MAX_LOG_LINES_PER_JOB: usize = 120;
@@ -26,6 +35,9 @@ pub struct QueueItem {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub thumbnail_url: Option<String>,
     pub url: String,
+    /// Uploader name from analysis; exposed to post-download hooks as `{channel}`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel: Option<String>,
     pub mode: DownloadMode,
     pub quality_id: String,
     pub status: String,
@@ -41,12 +53,121 @@ pub struct QueueItem {
     pub retry_count: i32,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub download_log: Option<Vec<String>>,
+    /// Per-job overrides for the embed defaults in `AppSettings`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embed_metadata: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embed_thumbnail: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embed_chapters: Option<bool>,
+    /// Per-job override for `AppSettings::sponsorblock_mode`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sponsorblock_mode: Option<SponsorBlockMode>,
+    /// Number of SponsorBlock segments yt-dlp reported for this job.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sponsorblock_segments: Option<u32>,
+    /// Per-job cookie source; overrides the settings cookies when present.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cookies_file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cookies_browser: Option<String>,
+    /// Expected download size from analysis, used by the disk space preflight.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub estimated_bytes: Option<u64>,
+    /// Duration from analysis, compared with the output by the integrity check.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_sec: Option<f64>,
+    /// `verified`, `suspect` or `broken` once the output has been probed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub integrity: Option<String>,
+    /// Classified cause of the last download failure.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_category: Option<RetryStrategy>,
+    /// Stream properties of the chosen quality, used to pick a replacement
+    /// if the format is gone when the job runs.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format_profile: Option<FormatProfile>,
+    /// Platform video id reported by analysis.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub video_id: Option<String>,
+    /// Upload date as `YYYYMMDD`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub upload_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub playlist: Option<String>,
+    /// 1-based position within `playlist`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub playlist_index: Option<u32>,
+    /// Destination folder chosen for this job, overriding the settings.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_dir: Option<String>,
+    /// Progress of copying the finished file to another volume.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub move_progress_percent: Option<f64>,
+    /// Size of the output file when the job completed, used to find it again
+    /// after the user renames it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_bytes: Option<u64>,
 }
 
 /// A snapshot of all queue items emitted to the frontend.
 #[derive(Debug, Clone, Serialize)]
 pub struct QueueSnapshot {
     pub items: Vec<QueueItem>,
+    /// Why the queue stopped dispatching jobs, if it did.
+    #[serde(rename = "pauseReason", skip_serializing_if = "Option::is_none")]
+    pub pause_reason: Option<String>,
+}
+
+/// The fields of one queue item that change while it runs, emitted as
+/// `job-updated`. Full `queue-updated` snapshots are reserved for structural
+/// changes (items added, removed or restored).
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct JobUpdate {
+    pub id: String,
+    pub status: String,
+    pub progress_percent: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub speed_text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub eta_text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_category: Option<RetryStrategy>,
+    pub retry_count: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sponsorblock_segments: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub integrity: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub move_progress_percent: Option<f64>,
+    /// Log lines appended since the previous update for this job.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub log_lines: Vec<String>,
+}
+
+impl JobUpdate {
+    pub fn from_item(item: &QueueItem, log_lines: Vec<String>) -> Self {
+        Self {
+            id: item.id.clone(),
+            status: item.status.clone(),
+            progress_percent: item.progress_percent,
+            speed_text: item.speed_text.clone(),
+            eta_text: item.eta_text.clone(),
+            output_path: item.output_path.clone(),
+            error_message: item.error_message.clone(),
+            error_category: item.error_category,
+            retry_count: item.retry_count,
+            sponsorblock_segments: item.sponsorblock_segments,
+            integrity: item.integrity.clone(),
+            move_progress_percent: item.move_progress_percent,
+            log_lines,
+        }
+    }
 }
 
 /// Result of a duplicate-URL check for a given mode and quality.
@@ -74,9 +195,24 @@ pub struct EnqueueInput {
     pub url: String,
     pub title: Option<String>,
     pub thumbnail_url: Option<String>,
+    pub channel: Option<String>,
     pub mode: DownloadMode,
     pub quality_id: String,
     pub force_duplicate: bool,
+    pub embed_metadata: Option<bool>,
+    pub embed_thumbnail: Option<bool>,
+    pub embed_chapters: Option<bool>,
+    pub sponsorblock_mode: Option<SponsorBlockMode>,
+    pub cookies_file: Option<String>,
+    pub cookies_browser: Option<String>,
+    pub estimated_bytes: Option<u64>,
+    pub duration_sec: Option<f64>,
+    pub format_profile: Option<FormatProfile>,
+    pub video_id: Option<String>,
+    pub upload_date: Option<String>,
+    pub playlist: Option<String>,
+    pub playlist_index: Option<u32>,
+    pub output_dir: Option<String>,
 }
 
 // ============================================================================
@@ -89,6 +225,7 @@ pub struct EnqueueInput {
 pub fn queue_snapshot(state: &AppState) -> QueueSnapshot {
     QueueSnapshot {
         items: state.queue.clone(),
+        pause_reason: state.queue_pause_reason.clone(),
     }
 }
 
@@ -97,24 +234,26 @@ pub fn emit_queue_updated(app: &AppHandle, state: &AppState) {
     let _ = app.emit("queue-updated", queue_snapshot(state));
 }
 
-/// Emits a queue-updated event from an already-built QueueSnapshot.
-pub fn emit_queue_updated_snapshot(app: &AppHandle, snapshot: QueueSnapshot) {
-    let _ = app.emit("queue-updated", snapshot);
+/// Emits a job-updated event for a single queue item.
+pub fn emit_job_updated(app: &AppHandle, update: JobUpdate) {
+    let _ = app.emit("job-updated", update);
 }
 
 // ============================================================================
 // Persistence helpers
 // ============================================================================
 
-/// Persists the current queue to disk atomically, writing a backup after success.
+/// Persists the current queue through the persistence actor, or writes it
+/// directly when the actor is not running. An immediate write is on disk once
+/// the returned ack is waited on or dropped; release the state lock first.
 // @MX:ANCHOR: [AUTO] All queue state persisted through this function. fan_in=8.
 // @MX:REASON: [AUTO] High fan_in: cancel_job, pause_job, resume_job, enqueue_job, download worker, clear_terminal_jobs use this path.
-pub fn persist_queue(app: &AppHandle, state: &AppState) {
-    let path = queue_file_path(app);
-    if let Ok(serialized) = serde_json::to_string_pretty(&state.queue) {
-        if write_atomic(&path, &serialized).is_ok() {
-            let bak_path = PathBuf::from(format!("{}.bak", path.display()));
-            let _ = write_atomic(&bak_path, &serialized);
+pub fn persist_queue(app: &AppHandle, state: &AppState, mode: PersistMode) -> PersistAck {
+    match app.try_state::<SharedPersister>() {
+        Some(persister) => persister.0.submit(state.queue.clone(), mode),
+        None => {
+            write_queue_file(&queue_file_path(app), &state.queue);
+            PersistAck::written()
         }
     }
 }
@@ -131,11 +270,78 @@ pub fn expected_extension(mode: &DownloadMode) -> &'static str {
     }
 }
 
-/// Builds a unique output path for a new download, avoiding collisions.
-pub fn build_unique_output_path(state: &AppState, title: &str, mode: &DownloadMode) -> PathBuf {
-    let ext = expected_extension(mode);
-    let base = sanitize_file_name(title);
-    let root = PathBuf::from(&state.settings.download_dir);
+/// Where a new download is written under the configured collision policy.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OutputTarget {
+    /// Download to this path, replacing anything already there.
+    Write(PathBuf),
+    /// A file exists here and the policy skips finished copies.
+    Existing(PathBuf),
+    /// A file exists here and the policy fails the job.
+    Conflict(PathBuf),
+}
+
+/// Destination folder for a job: its own `output_dir`, else the default
+/// folder for its mode, else `download_dir`.
+pub fn job_download_dir(settings: &AppSettings, job: &QueueItem) -> PathBuf {
+    let mode_dir = match job.mode {
+        DownloadMode::Audio => settings.audio_download_dir.as_ref(),
+        DownloadMode::Video => settings.video_download_dir.as_ref(),
+    };
+    PathBuf::from(
+        job.output_dir
+            .as_ref()
+            .or(mode_dir)
+            .unwrap_or(&settings.download_dir),
+    )
+}
+
+/// Folder, file stem and extension for a job from the file name template.
+/// Template folders are created when the file is moved into place.
+fn templated_output_parts(state: &AppState, job: &QueueItem) -> (PathBuf, String, &'static str) {
+    let (folder, base) = render_file_name_template(
+        &state.settings.file_name_template,
+        job,
+        &FileNamePolicy::for_filesystem(state.settings.target_filesystem),
+    );
+    (
+        job_download_dir(&state.settings, job).join(folder),
+        base,
+        expected_extension(&job.mode),
+    )
+}
+
+/// Resolves the output path for a job according to `collision_policy`.
+pub fn resolve_output_target(state: &AppState, job: &QueueItem) -> OutputTarget {
+    let (root, base, ext) = templated_output_parts(state, job);
+    let path = root.join(format!("{base}.{ext}"));
+    if is_own_incomplete_move(&path, job) {
+        return OutputTarget::Write(path);
+    }
+    match state.settings.collision_policy {
+        CollisionPolicy::AutoSuffix => OutputTarget::Write(build_unique_output_path(state, job)),
+        CollisionPolicy::Skip if path.exists() => OutputTarget::Existing(path),
+        CollisionPolicy::Fail if path.exists() => OutputTarget::Conflict(path),
+        CollisionPolicy::Overwrite | CollisionPolicy::Skip | CollisionPolicy::Fail => {
+            OutputTarget::Write(path)
+        }
+    }
+}
+
+/// True when `path` is a partial copy this job left behind: its `.incomplete`
+/// marker points into the job's temp dir, so the move resumes there.
+fn is_own_incomplete_move(path: &Path, job: &QueueItem) -> bool {
+    incomplete_move_source(path).is_some_and(|source| {
+        source
+            .components()
+            .any(|part| part.as_os_str() == job.id.as_str())
+    })
+}
+
+/// Builds a unique output path for a new download from the file name
+/// template, appending ` (N)` to avoid collisions.
+pub fn build_unique_output_path(state: &AppState, job: &QueueItem) -> PathBuf {
+    let (root, base, ext) = templated_output_parts(state, job);
     let mut suffix: i32 = 0;
 
     loop {
@@ -147,6 +353,9 @@ pub fn build_unique_output_path(state: &AppState, title: &str, mode: &DownloadMo
         let file_name = format!("{base}{suffix_label}.{ext}");
         let candidate = root.join(file_name);
         let exists_on_disk = candidate.exists();
+        if exists_on_disk && is_own_incomplete_move(&candidate, job) {
+            return candidate;
+        }
         let exists_in_queue = state.queue.iter().any(|item| {
             item.output_path
                 .as_ref()
@@ -191,12 +400,78 @@ pub fn append_download_log(item: &mut QueueItem, line: &str) -> bool {
     true
 }
 
-/// Scans the download directory for `.incomplete` marker files at startup.
-/// For each marker: finds matching queue item by output_path, marks it failed,
-/// then removes the marker regardless of whether a matching item was found.
+/// Scans the download directories for `.incomplete` marker files at startup.
+/// Markers whose source file still exists are kept so the job resumes the
+/// copy. For the rest: finds matching queue item by output_path, marks it
+/// failed, then removes the marker regardless of whether a matching item was found.
 pub fn scan_incomplete_markers(app: &AppHandle, state: &mut AppState) {
-    let download_dir = PathBuf::from(&state.settings.download_dir);
-    let entries = match fs::read_dir(&download_dir) {
+    for download_dir in configured_download_dirs(state) {
+        scan_incomplete_markers_in(state, &download_dir);
+    }
+    // Emit event to surface the updated queue state if app handle is available
+    let _ = app.emit("queue-updated", serde_json::json!({}));
+}
+
+/// Download folders in use: the configured ones plus per-job overrides.
+pub fn configured_download_dirs(state: &AppState) -> Vec<PathBuf> {
+    let mut download_dirs: Vec<PathBuf> = Vec::new();
+    let configured = [
+        Some(&state.settings.download_dir),
+        state.settings.audio_download_dir.as_ref(),
+        state.settings.video_download_dir.as_ref(),
+    ];
+    let per_job = state.queue.iter().map(|item| item.output_dir.as_ref());
+    for dir in configured.into_iter().chain(per_job).flatten() {
+        let dir = PathBuf::from(dir);
+        if !download_dirs.contains(&dir) {
+            download_dirs.push(dir);
+        }
+    }
+    download_dirs
+}
+
+/// Temp roots to clean (the app data root and the staging root of every
+/// download folder), the job ids whose partial downloads must be kept, and
+/// the age after which anything else is removed.
+pub fn temp_cleanup_targets(
+    app: &AppHandle,
+    state: &AppState,
+) -> (Vec<PathBuf>, HashSet<String>, Duration) {
+    let mut roots = vec![temp_downloads_root_dir(app)];
+    roots.extend(
+        configured_download_dirs(state)
+            .iter()
+            .map(|dir| staging_root(dir)),
+    );
+    let keep = state
+        .queue
+        .iter()
+        .filter(|item| matches!(item.status.as_str(), "queued" | "paused" | "downloading"))
+        .map(|item| item.id.clone())
+        .collect();
+    let max_age = Duration::from_secs(state.settings.temp_orphan_max_age_days * 24 * 60 * 60);
+    (roots, keep, max_age)
+}
+
+/// Cleans every temp root returned by `temp_cleanup_targets`.
+pub fn clean_temp_roots(
+    roots: &[PathBuf],
+    keep: &HashSet<String>,
+    max_age: Duration,
+) -> TempCleanupReport {
+    let now = SystemTime::now();
+    let mut report = TempCleanupReport::default();
+    for root in roots {
+        report.merge(clean_temp_job_dirs(root, keep, max_age, now));
+    }
+    report
+}
+
+/// Scans `download_dir` and its subfolders, where templated file names put
+/// downloads, for `.incomplete` markers. The staging folder is skipped since
+/// files there are still owned by their jobs.
+pub fn scan_incomplete_markers_in(state: &mut AppState, download_dir: &Path) {
+    let entries = match fs::read_dir(download_dir) {
         Ok(e) => e,
         Err(_) => return,
     };
@@ -206,12 +481,22 @@ pub fn scan_incomplete_markers(app: &AppHandle, state: &mut AppState) {
             .file_name()
             .and_then(|n| n.to_str())
             .unwrap_or_default();
+        if entry.file_type().is_ok_and(|kind| kind.is_dir()) {
+            if name != STAGING_DIR_NAME {
+                scan_incomplete_markers_in(state, &path);
+            }
+            continue;
+        }
         if !name.ends_with(".incomplete") {
             continue;
         }
         // Derive the intended destination path by stripping ".incomplete"
         let dest_str = name.trim_end_matches(".incomplete");
         let dest_path = download_dir.join(dest_str);
+        // The source is still in its temp dir: the job will resume the copy.
+        if incomplete_move_source(&dest_path).is_some_and(|source| source.exists()) {
+            continue;
+        }
         let dest_str_full = dest_path.to_string_lossy().to_string();
 
         // Find a matching queue item by output_path
@@ -227,8 +512,6 @@ pub fn scan_incomplete_markers(app: &AppHandle, state: &mut AppState) {
         // Remove the .incomplete marker regardless of match
         let _ = fs::remove_file(&path);
     }
-    // Emit event to surface the updated queue state if app handle is available
-    let _ = app.emit("queue-updated", serde_json::json!({}));
 }
 
 // ============================================================================
@@ -252,6 +535,7 @@ pub async fn check_duplicate(
             && item.quality_id == input.quality_id
             && item.status != "failed"
             && item.status != "canceled"
+            && item.status != "missing"
     });
     Ok(DuplicateCheckResult {
         is_duplicate: duplicate.is_some(),
@@ -268,6 +552,10 @@ pub async fn enqueue_job(
     input: EnqueueInput,
 ) -> CommandResult<Value> {
     let normalized_url = normalize_youtube_video_url(&input.url);
+    // Checked before taking the lock: the folder may be on a slow network share.
+    let output_dir = normalize_optional_text(input.output_dir)
+        .map(|dir| validate_output_dir(&dir))
+        .transpose()?;
 
     let mut locked = state
         .0
@@ -292,6 +580,7 @@ pub async fn enqueue_job(
         title: input.title.unwrap_or_else(|| normalized_url.clone()),
         thumbnail_url: input.thumbnail_url,
         url: normalized_url,
+        channel: normalize_optional_text(input.channel),
         mode: input.mode,
         quality_id: input.quality_id,
         status: "queued".to_string(),
@@ -302,12 +591,32 @@ pub async fn enqueue_job(
         error_message: None,
         retry_count: 0,
         download_log: Some(Vec::new()),
+        embed_metadata: input.embed_metadata,
+        embed_thumbnail: input.embed_thumbnail,
+        embed_chapters: input.embed_chapters,
+        sponsorblock_mode: input.sponsorblock_mode,
+        sponsorblock_segments: None,
+        cookies_file: normalize_optional_text(input.cookies_file),
+        cookies_browser: normalize_optional_text(input.cookies_browser),
+        estimated_bytes: input.estimated_bytes,
+        duration_sec: input.duration_sec.filter(|duration| *duration > 0.0),
+        integrity: None,
+        error_category: None,
+        format_profile: input.format_profile,
+        video_id: normalize_optional_text(input.video_id),
+        upload_date: normalize_optional_text(input.upload_date),
+        playlist: normalize_optional_text(input.playlist),
+        playlist_index: input.playlist_index.filter(|index| *index > 0),
+        output_dir,
+        move_progress_percent: None,
+        output_bytes: None,
     });
     emit_queue_updated(&app, &locked);
-    persist_queue(&app, &locked);
+    let saved = persist_queue(&app, &locked, PersistMode::Immediate);
     drop(locked);
+    saved.wait();
 
-    start_worker_if_needed(app.clone(), state.0.clone(), runtime.0.clone());
+    notify_worker_pool(&runtime.0);
     Ok(serde_json::json!({ "jobId": id }))
 }
 
@@ -327,20 +636,22 @@ pub async fn pause_job(
         item.status = "paused".to_string();
         item.speed_text = None;
         item.eta_text = None;
+        emit_job_updated(&app, JobUpdate::from_item(item, Vec::new()));
     }
     let snapshot = queue_snapshot(&state);
-    emit_queue_updated(&app, &state);
-    persist_queue(&app, &state);
+    let saved = persist_queue(&app, &state, PersistMode::Immediate);
+    drop(state);
+    saved.wait();
 
     let runtime = runtime.0.clone();
     std::thread::spawn(move || {
-        kill_active_child_unchecked(&runtime);
+        terminate_job_process(&runtime, &id);
     });
 
     Ok(snapshot)
 }
 
-/// Resumes a paused or failed job by re-queuing it and starting the worker.
+/// Resumes a paused or failed job by re-queuing it and notifying the worker pool.
 #[tauri::command]
 pub async fn resume_job(
     app: AppHandle,
@@ -355,13 +666,72 @@ pub async fn resume_job(
     if let Some(item) = locked.queue.iter_mut().find(|item| item.id == id) {
         item.status = "queued".to_string();
         item.error_message = None;
+        emit_job_updated(&app, JobUpdate::from_item(item, Vec::new()));
+    }
+    // Resuming a job also lifts a queue-level pause; the preflight re-checks space.
+    if locked.queue_pause_reason.take().is_some() {
+        emit_queue_updated(&app, &locked);
     }
     let snapshot = queue_snapshot(&locked);
+    persist_queue(&app, &locked, PersistMode::Deferred);
+    drop(locked);
+
+    notify_worker_pool(&runtime.0);
+    Ok(snapshot)
+}
+
+/// Queues a completed or missing job again so its file is downloaded anew.
+#[tauri::command]
+pub async fn redownload_job(
+    app: AppHandle,
+    state: State<'_, SharedState>,
+    runtime: State<'_, SharedRuntime>,
+    id: String,
+) -> CommandResult<QueueSnapshot> {
+    let mut locked = state
+        .0
+        .lock()
+        .map_err(|_| "state lock poisoned".to_string())?;
+    let item = locked
+        .queue
+        .iter_mut()
+        .find(|item| item.id == id && (item.status == "completed" || item.status == "missing"))
+        .ok_or_else(|| "다시 받을 수 있는 항목이 아닙니다.".to_string())?;
+    item.status = "queued".to_string();
+    item.progress_percent = 0.0;
+    item.retry_count = 0;
+    item.output_path = None;
+    item.output_bytes = None;
+    item.integrity = None;
+    item.error_message = None;
+    item.error_category = None;
+    emit_job_updated(&app, JobUpdate::from_item(item, Vec::new()));
+    let snapshot = queue_snapshot(&locked);
+    let saved = persist_queue(&app, &locked, PersistMode::Immediate);
+    drop(locked);
+    saved.wait();
+
+    notify_worker_pool(&runtime.0);
+    Ok(snapshot)
+}
+
+/// Lifts a queue-level pause (e.g. after low disk space) and resumes dispatching.
+#[tauri::command]
+pub async fn resume_queue(
+    app: AppHandle,
+    state: State<'_, SharedState>,
+    runtime: State<'_, SharedRuntime>,
+) -> CommandResult<QueueSnapshot> {
+    let mut locked = state
+        .0
+        .lock()
+        .map_err(|_| "state lock poisoned".to_string())?;
+    locked.queue_pause_reason = None;
+    let snapshot = queue_snapshot(&locked);
     emit_queue_updated(&app, &locked);
-    persist_queue(&app, &locked);
     drop(locked);
 
-    start_worker_if_needed(app.clone(), shared_state, runtime.0.clone());
+    notify_worker_pool(&runtime.0);
     Ok(snapshot)
 }
 
@@ -380,21 +750,22 @@ pub async fn cancel_job(
     if let Some(item) = state.queue.iter_mut().find(|item| item.id == id) {
         item.status = "canceled".to_string();
         item.error_message = Some("사용자 취소".to_string());
+        emit_job_updated(&app, JobUpdate::from_item(item, Vec::new()));
     }
     let snapshot = queue_snapshot(&state);
-    emit_queue_updated(&app, &state);
-    persist_queue(&app, &state);
+    let saved = persist_queue(&app, &state, PersistMode::Immediate);
     drop(state);
+    saved.wait();
 
     let runtime = runtime.0.clone();
     std::thread::spawn(move || {
-        kill_active_child_unchecked(&runtime);
+        terminate_job_process(&runtime, &id);
     });
 
     Ok(snapshot)
 }
 
-/// Removes all completed, failed, and canceled jobs from the queue.
+/// Removes all completed, missing, failed, and canceled jobs from the queue.
 #[tauri::command]
 pub async fn clear_terminal_jobs(
     app: AppHandle,
@@ -405,14 +776,35 @@ pub async fn clear_terminal_jobs(
         .lock()
         .map_err(|_| "state lock poisoned".to_string())?;
     state.queue.retain(|item| {
-        item.status != "completed" && item.status != "failed" && item.status != "canceled"
+        item.status != "completed"
+            && item.status != "missing"
+            && item.status != "failed"
+            && item.status != "canceled"
     });
     let snapshot = queue_snapshot(&state);
     emit_queue_updated(&app, &state);
-    persist_queue(&app, &state);
+    let saved = persist_queue(&app, &state, PersistMode::Immediate);
+    drop(state);
+    saved.wait();
     Ok(snapshot)
 }
 
+/// Removes temp folders no job will resume and reports the space reclaimed.
+#[tauri::command]
+pub async fn clean_temp_downloads(
+    app: AppHandle,
+    state: State<'_, SharedState>,
+) -> CommandResult<TempCleanupReport> {
+    let (roots, keep, max_age) = {
+        let locked = state
+            .0
+            .lock()
+            .map_err(|_| "state lock poisoned".to_string())?;
+        temp_cleanup_targets(&app, &locked)
+    };
+    Ok(clean_temp_roots(&roots, &keep, max_age))
+}
+
 /// Returns a snapshot of the current queue state.
 #[tauri::command]
 pub async fn get_queue_snapshot(state: State<'_, SharedState>) -> CommandResult<QueueSnapshot> {
@@ -442,6 +834,8 @@ pub async fn delete_file(
     });
     let snapshot = queue_snapshot(&state);
     emit_queue_updated(&app, &state);
-    persist_queue(&app, &state);
+    let saved = persist_queue(&app, &state, PersistMode::Immediate);
+    drop(state);
+    saved.wait();
     Ok(snapshot)
 }
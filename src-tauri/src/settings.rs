@@ -1,5 +1,15 @@
-use crate::file_ops::{normalize_download_dir, queue_file_path, settings_file_path, write_atomic};
+use crate::download::{
+    clamp_worker_count, normalize_retry_policy, resize_worker_pool, RetryPolicy, SharedRuntime,
+};
+use crate::file_ops::{
+    backup_generation_paths, normalize_download_dir, queue_file_path, settings_file_path,
+    validate_output_dir, write_atomic, write_backup_generation,
+};
+use crate::hooks::{normalize_post_download_hooks, PostDownloadHook};
+use crate::naming::{normalize_file_name_template, DEFAULT_FILE_NAME_TEMPLATE};
+use crate::network::normalize_proxy_url;
 use crate::types::CommandResult;
+use crate::utils::TargetFilesystem;
 use dirs::download_dir;
 use rfd::FileDialog;
 use serde::{Deserialize, Serialize};
@@ -12,6 +22,61 @@ use tauri::{AppHandle, Emitter, State};
 // Domain types
 // ============================================================================
 
+/// SponsorBlock categories that may be removed or marked during download.
+pub const SPONSORBLOCK_CATEGORIES: &[&str] = &["sponsor", "intro", "selfpromo"];
+
+/// Default free-space reserve kept on download volumes, in MiB.
+pub const DEFAULT_MIN_FREE_SPACE_MB: u64 = 1024;
+const MAX_MIN_FREE_SPACE_MB: u64 = 1024 * 1024;
+
+/// Default time without output or byte progress before a download is
+/// considered stalled, in seconds. Zero disables the watchdog.
+pub const DEFAULT_STALL_TIMEOUT_SEC: u64 = 120;
+const MIN_STALL_TIMEOUT_SEC: u64 = 30;
+const MAX_STALL_TIMEOUT_SEC: u64 = 3600;
+/// Orphaned temp job folders older than this many days are removed at startup.
+pub const DEFAULT_TEMP_ORPHAN_MAX_AGE_DAYS: u64 = 7;
+const MAX_TEMP_ORPHAN_MAX_AGE_DAYS: u64 = 365;
+
+/// How SponsorBlock segments are handled during download.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SponsorBlockMode {
+    #[default]
+    Off,
+    /// Cut the segments out of the output file.
+    Remove,
+    /// Keep the segments but add chapter markers around them.
+    Mark,
+}
+
+/// What happens when the output file of a new download already exists.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum CollisionPolicy {
+    /// Write `title (1).ext`, `title (2).ext`, ...
+    #[default]
+    AutoSuffix,
+    /// Replace the existing file.
+    Overwrite,
+    /// Mark the job completed if the existing file is a finished copy.
+    Skip,
+    /// Fail the job without downloading.
+    Fail,
+}
+
+/// Where partial downloads are staged before they are moved into place.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum TempLocation {
+    /// `tmp-downloads` in the app data folder.
+    #[default]
+    AppData,
+    /// A hidden folder inside the destination folder, so finishing a download
+    /// is a rename instead of a copy between volumes.
+    DestinationVolume,
+}
+
 /// Active application settings used at runtime.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -20,6 +85,65 @@ pub struct AppSettings {
     pub max_retries: i32,
     pub language: String,
     pub max_concurrent_downloads: i32,
+    #[serde(default)]
+    pub embed_metadata: bool,
+    #[serde(default)]
+    pub embed_thumbnail: bool,
+    #[serde(default)]
+    pub embed_chapters: bool,
+    #[serde(default)]
+    pub sponsorblock_mode: SponsorBlockMode,
+    #[serde(default = "default_sponsorblock_categories")]
+    pub sponsorblock_categories: Vec<String>,
+    /// Netscape-format cookies file passed to yt-dlp via `--cookies`.
+    #[serde(default)]
+    pub cookies_file: Option<String>,
+    /// Browser cookie profile passed via `--cookies-from-browser` (e.g. `chrome:Profile 1`).
+    #[serde(default)]
+    pub cookies_browser: Option<String>,
+    /// HTTP/HTTPS/SOCKS proxy used by yt-dlp and the internal HTTP clients.
+    #[serde(default)]
+    pub proxy_url: Option<String>,
+    /// Free space (MiB) that must remain on the temp and destination volumes
+    /// after a download; below this the queue is paused.
+    #[serde(default = "default_min_free_space_mb")]
+    pub min_free_space_mb: u64,
+    /// Commands run after a job completes or fails.
+    #[serde(default)]
+    pub post_download_hooks: Vec<PostDownloadHook>,
+    /// Per-category retry limits, delays and jitter.
+    #[serde(default)]
+    pub retry_policy: RetryPolicy,
+    /// Seconds without new output or bytes before a download is restarted; 0 disables.
+    #[serde(default = "default_stall_timeout_sec")]
+    pub stall_timeout_sec: u64,
+    /// Output path below `download_dir`, e.g. `{channel}/{upload_year}/{title} [{id}]`.
+    /// The extension is added from the download mode.
+    #[serde(default = "default_file_name_template")]
+    pub file_name_template: String,
+    /// How an existing file at the output path is handled.
+    #[serde(default)]
+    pub collision_policy: CollisionPolicy,
+    /// Default folder for audio downloads; `download_dir` when unset.
+    #[serde(default)]
+    pub audio_download_dir: Option<String>,
+    /// Default folder for video downloads; `download_dir` when unset.
+    #[serde(default)]
+    pub video_download_dir: Option<String>,
+    /// Filesystem whose naming rules output names follow.
+    #[serde(default)]
+    pub target_filesystem: TargetFilesystem,
+    /// Compare checksums after copying a download to another volume.
+    #[serde(default)]
+    pub verify_move_checksum: bool,
+    /// Check completed downloads for moved or deleted files at startup.
+    #[serde(default)]
+    pub reconcile_on_startup: bool,
+    #[serde(default)]
+    pub temp_location: TempLocation,
+    /// Age in days after which temp folders of unknown jobs are removed.
+    #[serde(default = "default_temp_orphan_max_age_days")]
+    pub temp_orphan_max_age_days: u64,
 }
 
 /// Partially-populated settings loaded from the persisted JSON file.
@@ -30,6 +154,27 @@ pub struct PersistedSettings {
     pub max_retries: Option<i32>,
     pub language: Option<String>,
     pub max_concurrent_downloads: Option<i32>,
+    pub embed_metadata: Option<bool>,
+    pub embed_thumbnail: Option<bool>,
+    pub embed_chapters: Option<bool>,
+    pub sponsorblock_mode: Option<SponsorBlockMode>,
+    pub sponsorblock_categories: Option<Vec<String>>,
+    pub cookies_file: Option<String>,
+    pub cookies_browser: Option<String>,
+    pub proxy_url: Option<String>,
+    pub min_free_space_mb: Option<u64>,
+    pub post_download_hooks: Option<Vec<PostDownloadHook>>,
+    pub retry_policy: Option<RetryPolicy>,
+    pub stall_timeout_sec: Option<u64>,
+    pub file_name_template: Option<String>,
+    pub collision_policy: Option<CollisionPolicy>,
+    pub audio_download_dir: Option<String>,
+    pub video_download_dir: Option<String>,
+    pub target_filesystem: Option<TargetFilesystem>,
+    pub verify_move_checksum: Option<bool>,
+    pub reconcile_on_startup: Option<bool>,
+    pub temp_location: Option<TempLocation>,
+    pub temp_orphan_max_age_days: Option<u64>,
 }
 
 // ============================================================================
@@ -44,6 +189,8 @@ pub struct AppState {
     pub queue: Vec<crate::queue::QueueItem>,
     pub settings: AppSettings,
     pub active_worker_count: usize,
+    /// Set when the worker pool stops dispatching, e.g. on low disk space.
+    pub queue_pause_reason: Option<String>,
 }
 
 /// Thread-safe wrapper for AppState.
@@ -54,6 +201,67 @@ pub struct SharedState(pub Arc<Mutex<AppState>>);
 // Default settings
 // ============================================================================
 
+/// Returns every supported SponsorBlock category.
+pub fn default_sponsorblock_categories() -> Vec<String> {
+    SPONSORBLOCK_CATEGORIES
+        .iter()
+        .map(|category| category.to_string())
+        .collect()
+}
+
+/// Default free-space reserve in MiB.
+pub fn default_min_free_space_mb() -> u64 {
+    DEFAULT_MIN_FREE_SPACE_MB
+}
+
+/// Default stall watchdog threshold in seconds.
+pub fn default_stall_timeout_sec() -> u64 {
+    DEFAULT_STALL_TIMEOUT_SEC
+}
+
+/// Default orphaned temp folder age limit in days.
+pub fn default_temp_orphan_max_age_days() -> u64 {
+    DEFAULT_TEMP_ORPHAN_MAX_AGE_DAYS
+}
+
+/// Default output file name template (`{title}`).
+pub fn default_file_name_template() -> String {
+    DEFAULT_FILE_NAME_TEMPLATE.to_string()
+}
+
+/// Keeps 0 (disabled) and clamps other thresholds to 30..=3600 seconds.
+pub fn normalize_stall_timeout_sec(value: u64) -> u64 {
+    if value == 0 {
+        0
+    } else {
+        value.clamp(MIN_STALL_TIMEOUT_SEC, MAX_STALL_TIMEOUT_SEC)
+    }
+}
+
+/// Clamps `max_concurrent_downloads` to the range the worker pool accepts.
+pub fn normalize_max_concurrent_downloads(value: i32) -> i32 {
+    clamp_worker_count(value.max(1) as usize) as i32
+}
+
+/// Trims an optional text setting, mapping blank values to `None`.
+pub fn normalize_optional_text(value: Option<String>) -> Option<String> {
+    value
+        .map(|text| text.trim().to_string())
+        .filter(|text| !text.is_empty())
+}
+
+/// Drops unknown and duplicate categories, preserving order.
+pub fn normalize_sponsorblock_categories(categories: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for category in categories {
+        let category = category.trim().to_lowercase();
+        if SPONSORBLOCK_CATEGORIES.contains(&category.as_str()) && !normalized.contains(&category) {
+            normalized.push(category);
+        }
+    }
+    normalized
+}
+
 /// Creates the default AppSettings: uses the system download directory.
 pub fn default_settings() -> AppSettings {
     AppSettings {
@@ -64,6 +272,27 @@ pub fn default_settings() -> AppSettings {
         max_retries: 3,
         language: "ko".to_string(),
         max_concurrent_downloads: 2,
+        embed_metadata: false,
+        embed_thumbnail: false,
+        embed_chapters: false,
+        sponsorblock_mode: SponsorBlockMode::Off,
+        sponsorblock_categories: default_sponsorblock_categories(),
+        cookies_file: None,
+        cookies_browser: None,
+        proxy_url: None,
+        min_free_space_mb: DEFAULT_MIN_FREE_SPACE_MB,
+        post_download_hooks: Vec::new(),
+        retry_policy: RetryPolicy::default(),
+        stall_timeout_sec: DEFAULT_STALL_TIMEOUT_SEC,
+        file_name_template: default_file_name_template(),
+        collision_policy: CollisionPolicy::AutoSuffix,
+        audio_download_dir: None,
+        video_download_dir: None,
+        target_filesystem: TargetFilesystem::Portable,
+        verify_move_checksum: false,
+        reconcile_on_startup: false,
+        temp_location: TempLocation::AppData,
+        temp_orphan_max_age_days: DEFAULT_TEMP_ORPHAN_MAX_AGE_DAYS,
     }
 }
 
@@ -71,15 +300,14 @@ pub fn default_settings() -> AppSettings {
 // Persistence helpers
 // ============================================================================
 
-/// Persists settings to disk atomically, writing a backup after success.
+/// Persists settings to disk atomically, refreshing the backup generations after success.
 // @MX:ANCHOR: [AUTO] Central settings persistence point — all configuration changes flow through here.
 // @MX:REASON: [AUTO] High fan_in: set_settings command and initialization code call this to persist user preferences.
 pub fn persist_settings(app: &AppHandle, settings: &AppSettings) {
     let path = settings_file_path(app);
     if let Ok(serialized) = serde_json::to_string_pretty(settings) {
         if write_atomic(&path, &serialized).is_ok() {
-            let bak_path = PathBuf::from(format!("{}.bak", path.display()));
-            let _ = write_atomic(&bak_path, &serialized);
+            let _ = write_backup_generation(&path, &serialized);
         }
     }
 }
@@ -96,7 +324,73 @@ pub fn apply_persisted_settings(state: &mut AppState, parsed: PersistedSettings)
         state.settings.language = language;
     }
     if let Some(max_concurrent) = parsed.max_concurrent_downloads {
-        state.settings.max_concurrent_downloads = max_concurrent.clamp(1, 3);
+        state.settings.max_concurrent_downloads =
+            normalize_max_concurrent_downloads(max_concurrent);
+    }
+    if let Some(embed_metadata) = parsed.embed_metadata {
+        state.settings.embed_metadata = embed_metadata;
+    }
+    if let Some(embed_thumbnail) = parsed.embed_thumbnail {
+        state.settings.embed_thumbnail = embed_thumbnail;
+    }
+    if let Some(embed_chapters) = parsed.embed_chapters {
+        state.settings.embed_chapters = embed_chapters;
+    }
+    if let Some(sponsorblock_mode) = parsed.sponsorblock_mode {
+        state.settings.sponsorblock_mode = sponsorblock_mode;
+    }
+    if let Some(categories) = parsed.sponsorblock_categories {
+        state.settings.sponsorblock_categories = normalize_sponsorblock_categories(&categories);
+    }
+    if parsed.cookies_file.is_some() {
+        state.settings.cookies_file = normalize_optional_text(parsed.cookies_file);
+    }
+    if parsed.cookies_browser.is_some() {
+        state.settings.cookies_browser = normalize_optional_text(parsed.cookies_browser);
+    }
+    if let Some(proxy_url) = parsed.proxy_url {
+        state.settings.proxy_url = normalize_proxy_url(Some(&proxy_url)).unwrap_or(None);
+    }
+    if let Some(min_free_space_mb) = parsed.min_free_space_mb {
+        state.settings.min_free_space_mb = min_free_space_mb.min(MAX_MIN_FREE_SPACE_MB);
+    }
+    if let Some(hooks) = parsed.post_download_hooks {
+        state.settings.post_download_hooks = normalize_post_download_hooks(hooks);
+    }
+    if let Some(retry_policy) = parsed.retry_policy {
+        state.settings.retry_policy = normalize_retry_policy(retry_policy);
+    }
+    if let Some(stall_timeout_sec) = parsed.stall_timeout_sec {
+        state.settings.stall_timeout_sec = normalize_stall_timeout_sec(stall_timeout_sec);
+    }
+    if let Some(template) = parsed.file_name_template {
+        state.settings.file_name_template = normalize_file_name_template(&template);
+    }
+    if let Some(collision_policy) = parsed.collision_policy {
+        state.settings.collision_policy = collision_policy;
+    }
+    if parsed.audio_download_dir.is_some() {
+        state.settings.audio_download_dir = normalize_optional_text(parsed.audio_download_dir)
+            .map(|dir| normalize_download_dir(&dir));
+    }
+    if parsed.video_download_dir.is_some() {
+        state.settings.video_download_dir = normalize_optional_text(parsed.video_download_dir)
+            .map(|dir| normalize_download_dir(&dir));
+    }
+    if let Some(target_filesystem) = parsed.target_filesystem {
+        state.settings.target_filesystem = target_filesystem;
+    }
+    if let Some(verify_move_checksum) = parsed.verify_move_checksum {
+        state.settings.verify_move_checksum = verify_move_checksum;
+    }
+    if let Some(reconcile_on_startup) = parsed.reconcile_on_startup {
+        state.settings.reconcile_on_startup = reconcile_on_startup;
+    }
+    if let Some(temp_location) = parsed.temp_location {
+        state.settings.temp_location = temp_location;
+    }
+    if let Some(max_age_days) = parsed.temp_orphan_max_age_days {
+        state.settings.temp_orphan_max_age_days = max_age_days.min(MAX_TEMP_ORPHAN_MAX_AGE_DAYS);
     }
 }
 
@@ -106,12 +400,12 @@ pub fn apply_persisted_settings(state: &mut AppState, parsed: PersistedSettings)
 
 /// Generic file-based loader with backup recovery.
 ///
-/// Attempts to read `primary`, then `backup` on parse failure.
-/// Returns `T::default()` if both fail.
+/// Attempts to read `primary`, then each of `backups` (newest first) on parse
+/// failure. Leaves the state untouched if none of them parses.
 fn load_json_with_recovery<T, F>(
     app: &AppHandle,
     primary: &Path,
-    backup: &Path,
+    backups: &[PathBuf],
     mut apply_fn: F,
     corruption_recovered_event: &str,
     corruption_unrecoverable_event: &str,
@@ -127,27 +421,18 @@ fn load_json_with_recovery<T, F>(
             Ok(parsed) => {
                 apply_fn(app, parsed, false);
             }
-            Err(parse_err) => match fs::read_to_string(backup) {
-                Ok(bak_content) => match serde_json::from_str::<T>(&bak_content) {
-                    Ok(bak_parsed) => {
-                        apply_fn(app, bak_parsed, true);
-                        let _ = app.emit(
-                            corruption_recovered_event,
-                            serde_json::json!({
-                                "message": "Restored from backup"
-                            }),
-                        );
-                    }
-                    Err(_) => {
-                        let _ = app.emit(
-                            corruption_unrecoverable_event,
-                            serde_json::json!({
-                                "error": parse_err.to_string()
-                            }),
-                        );
-                    }
-                },
-                Err(_) => {
+            Err(parse_err) => match first_valid_backup::<T>(backups) {
+                Some((bak_parsed, bak_path)) => {
+                    apply_fn(app, bak_parsed, true);
+                    let _ = app.emit(
+                        corruption_recovered_event,
+                        serde_json::json!({
+                            "message": "Restored from backup",
+                            "backup": bak_path.display().to_string()
+                        }),
+                    );
+                }
+                None => {
                     let _ = app.emit(
                         corruption_unrecoverable_event,
                         serde_json::json!({
@@ -160,26 +445,38 @@ fn load_json_with_recovery<T, F>(
     }
 }
 
+/// Newest backup in `backups` that still parses, with its path.
+pub fn first_valid_backup<T: serde::de::DeserializeOwned>(
+    backups: &[PathBuf],
+) -> Option<(T, PathBuf)> {
+    backups.iter().find_map(|path| {
+        let content = fs::read_to_string(path).ok()?;
+        serde_json::from_str::<T>(&content)
+            .ok()
+            .map(|parsed| (parsed, path.clone()))
+    })
+}
+
 /// Loads settings with backup recovery per SPEC-STABILITY-002 REQ-003.
 // @MX:NOTE: [AUTO] Adds backup recovery and corruption events per SPEC-STABILITY-002.
 pub fn load_settings_with_recovery(app: &AppHandle, state: &mut AppState) {
     let path = settings_file_path(app);
-    let bak_path = PathBuf::from(format!("{}.bak", path.display()));
+    let backups = backup_generation_paths(&path);
 
     load_json_with_recovery::<PersistedSettings, _>(
         app,
         &path,
-        &bak_path,
+        &backups,
         |_app, parsed, restored| {
             apply_persisted_settings(state, parsed);
             if restored {
-                // Primary already rewritten inside apply; write backup of restored settings
+                // Rewrite the corrupt primary from the restored backup.
                 if let Ok(serialized) = serde_json::to_string_pretty(&state.settings) {
                     let _ = write_atomic(&path, &serialized);
                 }
             } else if let Ok(serialized) = serde_json::to_string_pretty(&state.settings) {
                 // Valid primary: write backup
-                let _ = write_atomic(&bak_path, &serialized);
+                let _ = write_backup_generation(&path, &serialized);
             }
         },
         "settings-corruption-recovered",
@@ -207,12 +504,12 @@ pub fn normalize_queue_items(items: &mut [crate::queue::QueueItem]) {
 // @MX:NOTE: [AUTO] Adds backup recovery and corruption events per SPEC-STABILITY-002.
 pub fn load_queue_with_recovery(app: &AppHandle, state: &mut AppState) {
     let path = queue_file_path(app);
-    let bak_path = PathBuf::from(format!("{}.bak", path.display()));
+    let backups = backup_generation_paths(&path);
 
     load_json_with_recovery::<Vec<crate::queue::QueueItem>, _>(
         app,
         &path,
-        &bak_path,
+        &backups,
         |_app, mut parsed, restored| {
             normalize_queue_items(&mut parsed);
             state.queue = parsed;
@@ -221,7 +518,7 @@ pub fn load_queue_with_recovery(app: &AppHandle, state: &mut AppState) {
                     let _ = write_atomic(&path, &serialized);
                 }
             } else if let Ok(serialized) = serde_json::to_string_pretty(&state.queue) {
-                let _ = write_atomic(&bak_path, &serialized);
+                let _ = write_backup_generation(&path, &serialized);
             }
         },
         "queue-corruption-recovered",
@@ -248,8 +545,16 @@ pub async fn get_settings(state: State<'_, SharedState>) -> CommandResult<AppSet
 pub async fn set_settings(
     app: AppHandle,
     state: State<'_, SharedState>,
+    runtime: State<'_, SharedRuntime>,
     settings: AppSettings,
 ) -> CommandResult<()> {
+    let proxy_url = normalize_proxy_url(settings.proxy_url.as_deref())?;
+    let audio_download_dir = normalize_optional_text(settings.audio_download_dir)
+        .map(|dir| validate_output_dir(&dir))
+        .transpose()?;
+    let video_download_dir = normalize_optional_text(settings.video_download_dir)
+        .map(|dir| validate_output_dir(&dir))
+        .transpose()?;
     let mut state = state
         .0
         .lock()
@@ -258,9 +563,40 @@ pub async fn set_settings(
         download_dir: normalize_download_dir(&settings.download_dir),
         max_retries: settings.max_retries.clamp(0, 10),
         language: settings.language,
-        max_concurrent_downloads: settings.max_concurrent_downloads.clamp(1, 3),
+        max_concurrent_downloads: normalize_max_concurrent_downloads(
+            settings.max_concurrent_downloads,
+        ),
+        embed_metadata: settings.embed_metadata,
+        embed_thumbnail: settings.embed_thumbnail,
+        embed_chapters: settings.embed_chapters,
+        sponsorblock_mode: settings.sponsorblock_mode,
+        sponsorblock_categories: normalize_sponsorblock_categories(
+            &settings.sponsorblock_categories,
+        ),
+        cookies_file: normalize_optional_text(settings.cookies_file),
+        cookies_browser: normalize_optional_text(settings.cookies_browser),
+        proxy_url,
+        min_free_space_mb: settings.min_free_space_mb.min(MAX_MIN_FREE_SPACE_MB),
+        post_download_hooks: normalize_post_download_hooks(settings.post_download_hooks),
+        retry_policy: normalize_retry_policy(settings.retry_policy),
+        stall_timeout_sec: normalize_stall_timeout_sec(settings.stall_timeout_sec),
+        file_name_template: normalize_file_name_template(&settings.file_name_template),
+        collision_policy: settings.collision_policy,
+        audio_download_dir,
+        video_download_dir,
+        target_filesystem: settings.target_filesystem,
+        verify_move_checksum: settings.verify_move_checksum,
+        reconcile_on_startup: settings.reconcile_on_startup,
+        temp_location: settings.temp_location,
+        temp_orphan_max_age_days: settings
+            .temp_orphan_max_age_days
+            .min(MAX_TEMP_ORPHAN_MAX_AGE_DAYS),
     };
     persist_settings(&app, &state.settings);
+    let max_concurrent = state.settings.max_concurrent_downloads as usize;
+    drop(state);
+
+    resize_worker_pool(&runtime.0, max_concurrent);
     Ok(())
 }
 
@@ -1,4 +1,7 @@
 use regex::Regex;
+use serde::{Deserialize, Serialize};
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
 use url::Url;
 
 /// Normalizes a YouTube URL to canonical watch format.
@@ -51,20 +54,164 @@ pub fn normalize_youtube_video_url(raw_url: &str) -> String {
     input.to_string()
 }
 
-/// Sanitizes a file name by replacing forbidden characters with underscores,
-/// collapsing whitespace, trimming trailing dots/spaces, and truncating to 160 chars.
+/// Names Windows refuses for files regardless of extension.
+const WINDOWS_RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Length units left free for the ` (N)` collision suffix, the extension and
+/// the `.incomplete` marker written next to the final file.
+const FILE_NAME_RESERVED_UNITS: usize = 32;
+
+/// Names are also capped at this many characters to stay readable.
+const MAX_FILE_NAME_CHARS: usize = 160;
+
+/// Filesystem whose naming rules output file names must satisfy.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TargetFilesystem {
+    /// Valid everywhere: 255 bytes, NFC and no Windows reserved names.
+    #[default]
+    Portable,
+    /// NTFS and exFAT: 255 UTF-16 units and Windows reserved names.
+    Ntfs,
+    /// ext4, Btrfs and most Linux filesystems: 255 bytes.
+    Ext4,
+    /// APFS and HFS+: 255 bytes, decomposed (NFD) names.
+    Apfs,
+}
+
+/// How the length limit of a file name is measured.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NameLengthLimit {
+    Utf8Bytes(usize),
+    Utf16Units(usize),
+}
+
+impl NameLengthLimit {
+    fn max(self) -> usize {
+        match self {
+            NameLengthLimit::Utf8Bytes(max) | NameLengthLimit::Utf16Units(max) => max,
+        }
+    }
+
+    fn units(self, ch: char) -> usize {
+        match self {
+            NameLengthLimit::Utf8Bytes(_) => ch.len_utf8(),
+            NameLengthLimit::Utf16Units(_) => ch.len_utf16(),
+        }
+    }
+}
+
+/// Unicode normalization form written to disk.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UnicodeForm {
+    Nfc,
+    Nfd,
+}
+
+/// Naming rules of one target filesystem.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FileNamePolicy {
+    pub max_length: NameLengthLimit,
+    pub unicode_form: UnicodeForm,
+    /// Avoid `CON`, `NUL`, `COM1`, ... which Windows cannot open.
+    pub avoid_windows_reserved: bool,
+}
+
+impl FileNamePolicy {
+    pub fn for_filesystem(filesystem: TargetFilesystem) -> Self {
+        let (max_length, unicode_form, avoid_windows_reserved) = match filesystem {
+            TargetFilesystem::Portable => (NameLengthLimit::Utf8Bytes(255), UnicodeForm::Nfc, true),
+            TargetFilesystem::Ntfs => (NameLengthLimit::Utf16Units(255), UnicodeForm::Nfc, true),
+            TargetFilesystem::Ext4 => (NameLengthLimit::Utf8Bytes(255), UnicodeForm::Nfc, false),
+            TargetFilesystem::Apfs => (NameLengthLimit::Utf8Bytes(255), UnicodeForm::Nfd, false),
+        };
+        Self {
+            max_length,
+            unicode_form,
+            avoid_windows_reserved,
+        }
+    }
+}
+
+impl Default for FileNamePolicy {
+    fn default() -> Self {
+        Self::for_filesystem(TargetFilesystem::default())
+    }
+}
+
+/// Sanitizes a file name with the portable policy. See `sanitize_file_name_for`.
+pub fn sanitize_file_name(raw_name: &str) -> String {
+    sanitize_file_name_for(raw_name, &FileNamePolicy::default())
+}
+
+/// Sanitizes a file name (without extension) for `policy`: normalizes
+/// Unicode, collapses whitespace, replaces forbidden and control characters
+/// with underscores, trims trailing dots/spaces, truncates to the
+/// policy's length budget and renames Windows reserved names.
 ///
 /// Returns `"download"` if the result would be empty.
-pub fn sanitize_file_name(raw_name: &str) -> String {
-    let re = Regex::new(r#"[\\/:*?"<>|]"#).unwrap_or_else(|_| unreachable!());
-    let replaced = re.replace_all(raw_name, "_");
-    let collapsed = replaced.split_whitespace().collect::<Vec<&str>>().join(" ");
-    let trimmed = collapsed.trim().trim_end_matches(['.', ' ']).to_string();
+pub fn sanitize_file_name_for(raw_name: &str, policy: &FileNamePolicy) -> String {
+    let normalized: String = match policy.unicode_form {
+        UnicodeForm::Nfc => raw_name.nfc().collect(),
+        UnicodeForm::Nfd => raw_name.nfd().collect(),
+    };
+    let re = Regex::new(r#"[\\/:*?"<>|\x00-\x1f\x7f]"#).unwrap_or_else(|_| unreachable!());
+    let collapsed = normalized
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ");
+    let replaced = re.replace_all(&collapsed, "_");
+    let budget = policy
+        .max_length
+        .max()
+        .saturating_sub(FILE_NAME_RESERVED_UNITS);
+    let truncated = truncate_file_name(&replaced, budget, policy.max_length);
+    let trimmed = truncated.trim_end_matches(['.', ' ']);
     if trimmed.is_empty() {
-        "download".to_string()
-    } else {
-        trimmed.chars().take(160).collect()
+        return "download".to_string();
+    }
+    if policy.avoid_windows_reserved && is_windows_reserved_name(trimmed) {
+        // `CON.backup` is reserved too: mark the part before the first dot.
+        let (base, rest) = trimmed.split_at(trimmed.find('.').unwrap_or(trimmed.len()));
+        return format!("{base}_{rest}");
+    }
+    trimmed.to_string()
+}
+
+/// Cuts `name` to `budget` units and `MAX_FILE_NAME_CHARS` characters without
+/// separating a base character from the marks or jamo that follow it.
+fn truncate_file_name(name: &str, budget: usize, limit: NameLengthLimit) -> &str {
+    let mut used = 0;
+    for (count, (index, ch)) in name.char_indices().enumerate() {
+        let units = limit.units(ch);
+        if count < MAX_FILE_NAME_CHARS && used + units <= budget {
+            used += units;
+            continue;
+        }
+        let mut cut = index;
+        while cut > 0 && name[cut..].chars().next().is_some_and(continues_character) {
+            cut = name[..cut].char_indices().next_back().map_or(0, |(i, _)| i);
+        }
+        // A name of nothing but marks has no base character to keep them with.
+        return &name[..if cut == 0 { index } else { cut }];
     }
+    name
+}
+
+/// True for combining marks and the vowel/final jamo of decomposed Hangul.
+fn continues_character(ch: char) -> bool {
+    is_combining_mark(ch) || ('\u{1160}'..='\u{11FF}').contains(&ch)
+}
+
+/// True when the part before the first dot is a Windows device name.
+fn is_windows_reserved_name(name: &str) -> bool {
+    let base = name.split('.').next().unwrap_or_default().trim_end();
+    WINDOWS_RESERVED_NAMES
+        .iter()
+        .any(|reserved| base.eq_ignore_ascii_case(reserved))
 }
 
 /// Extracts the download progress percentage from a yt-dlp output line.
@@ -100,3 +247,56 @@ pub fn parse_eta(line: &str) -> Option<String> {
     let eta = line.find(" ETA ")?;
     Some(line[eta + 5..].trim().to_string())
 }
+
+/// Extracts the SponsorBlock segment count from a yt-dlp output line.
+///
+/// Matches `[SponsorBlock] Found N segments in the SponsorBlock database`, and
+/// reports `Some(0)` for the "No matching segments" message.
+pub fn parse_sponsorblock_segment_count(line: &str) -> Option<u32> {
+    let rest = line.trim().strip_prefix("[SponsorBlock]")?.trim();
+    if rest.starts_with("No matching segments") {
+        return Some(0);
+    }
+    let count = rest.strip_prefix("Found ")?.split_whitespace().next()?;
+    if !rest.contains("segment") {
+        return None;
+    }
+    count.parse::<u32>().ok()
+}
+
+/// Estimates the download size of one yt-dlp format entry in bytes.
+///
+/// Prefers `filesize`, then `filesize_approx`, then `tbr` (kbit/s) × duration.
+pub fn estimate_format_bytes(format: &serde_json::Value, duration_sec: f64) -> Option<u64> {
+    let reported = ["filesize", "filesize_approx"]
+        .iter()
+        .filter_map(|key| format.get(*key).and_then(serde_json::Value::as_f64))
+        .find(|bytes| *bytes > 0.0);
+    if let Some(bytes) = reported {
+        return Some(bytes as u64);
+    }
+    let kbps = format
+        .get("tbr")
+        .and_then(serde_json::Value::as_f64)
+        .filter(|kbps| *kbps > 0.0)?;
+    if duration_sec <= 0.0 {
+        return None;
+    }
+    Some((kbps * 1000.0 / 8.0 * duration_sec) as u64)
+}
+
+/// Formats a byte count for user-facing messages (e.g. `1.5 GB`).
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
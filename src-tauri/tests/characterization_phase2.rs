@@ -348,7 +348,7 @@ fn test_characterize_resolve_downloaded_file_path_prioritized() {
         if candidates.is_empty() {
             return Err("완성 파일을 임시 폴더에서 찾지 못했습니다.".to_string());
         }
-        candidates.sort_by(|a, b| b.1.cmp(&a.1));
+        candidates.sort_by_key(|c| std::cmp::Reverse(c.1));
         Ok(candidates[0].0.clone())
     }
 
@@ -412,7 +412,7 @@ fn test_characterize_resolve_downloaded_file_path_no_match() {
         if candidates.is_empty() {
             return Err("완성 파일을 임시 폴더에서 찾지 못했습니다.".to_string());
         }
-        candidates.sort_by(|a, b| b.1.cmp(&a.1));
+        candidates.sort_by_key(|c| std::cmp::Reverse(c.1));
         Ok(candidates[0].0.clone())
     }
 
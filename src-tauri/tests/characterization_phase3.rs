@@ -3,6 +3,9 @@
 // before extraction into separate modules.
 // Purpose: Verify behavior is preserved after transformation.
 
+// Mirrored logic is kept verbatim, including branches clippy would simplify.
+#![allow(clippy::if_same_then_else, clippy::useless_vec)]
+
 // ============================================================================
 // Settings domain characterization tests
 // ============================================================================
@@ -30,16 +33,18 @@ fn test_characterize_settings_max_retries_clamped() {
     assert_eq!(valid, 5);
 }
 
-/// Characterize: max_concurrent_downloads is clamped to [1, 3] when set.
+/// Characterize: max_concurrent_downloads is clamped to [1, MAX_CONCURRENT_DOWNLOADS] when set.
 #[test]
 fn test_characterize_settings_max_concurrent_clamped() {
-    let too_high = 99i32.clamp(1, 3);
-    assert_eq!(too_high, 3);
+    use tubeextract_lib::{normalize_max_concurrent_downloads, MAX_CONCURRENT_DOWNLOADS};
+
+    let too_high = normalize_max_concurrent_downloads(99);
+    assert_eq!(too_high, MAX_CONCURRENT_DOWNLOADS as i32);
 
-    let too_low = 0i32.clamp(1, 3);
+    let too_low = normalize_max_concurrent_downloads(0);
     assert_eq!(too_low, 1);
 
-    let valid = 2i32.clamp(1, 3);
+    let valid = normalize_max_concurrent_downloads(2);
     assert_eq!(valid, 2);
 }
 
@@ -137,6 +142,7 @@ fn test_characterize_cancel_job_error_message() {
 
 /// Characterize: resume_job sets status to "queued" and clears error_message.
 #[test]
+#[allow(unused_assignments)]
 fn test_characterize_resume_job_clears_error() {
     let mut status = "paused".to_string();
     let mut error_message: Option<String> = Some("prior error".to_string());
@@ -151,6 +157,7 @@ fn test_characterize_resume_job_clears_error() {
 
 /// Characterize: pause_job clears speed_text and eta_text.
 #[test]
+#[allow(unused_assignments)]
 fn test_characterize_pause_job_clears_speed_eta() {
     let mut speed_text: Option<String> = Some("1.2 MiB/s".to_string());
     let mut eta_text: Option<String> = Some("00:30".to_string());
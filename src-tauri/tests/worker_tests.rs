@@ -0,0 +1,1074 @@
+// Download worker pool tests driven by ScriptedBackend.
+// These exercise the real dispatcher and workers (status transitions, retry,
+// file move, resizing) without a yt-dlp binary or a Tauri runtime.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tubeextract_lib::{
+    clean_temp_job_dirs, default_settings, shutdown_worker_pool, terminate_job_process,
+    ActiveProcess, AppState, CollisionPolicy, CommandCaptureResult, DownloadBackend,
+    DownloadProcess, FormatProfile, HookTrigger, JobUpdate, PersistAck, PersistMode,
+    PostDownloadHook, QueueItem, RetryRule, RetryStrategy, RuntimeState, ScriptedAttempt,
+    ScriptedBackend, TempLocation, WorkerHost, WorkerPool, STAGING_DIR_NAME,
+};
+
+// =============================================================================
+// Test host
+// =============================================================================
+
+/// In-memory `WorkerHost` that records emitted job updates and persists nothing.
+struct TestHost {
+    temp_root: PathBuf,
+    emitted: Mutex<Vec<JobUpdate>>,
+    /// Free space reported for every volume; `None` skips the preflight.
+    free_space: Mutex<Option<u64>>,
+    /// Commands passed to `run_command`, in order.
+    commands: Mutex<Vec<(String, Vec<String>)>>,
+    /// Exit code and stdout returned by `probe_media`; `None` means no ffprobe.
+    probe: Mutex<Option<(i32, String)>>,
+    /// Metadata returned by `fetch_formats`; `None` makes the lookup fail.
+    formats: Mutex<Option<serde_json::Value>>,
+}
+
+impl WorkerHost for TestHost {
+    fn emit_job(&self, update: JobUpdate) {
+        self.emitted.lock().unwrap().push(update);
+    }
+
+    fn emit_queue(&self, _state: &AppState) {}
+
+    fn persist_queue(&self, _state: &AppState, _mode: PersistMode) -> PersistAck {
+        PersistAck::written()
+    }
+
+    fn temp_job_dir(&self, job_id: &str) -> PathBuf {
+        self.temp_root.join(job_id)
+    }
+
+    fn wait_for_dependencies(&self) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn available_space(&self, _path: &Path) -> Option<u64> {
+        *self.free_space.lock().unwrap()
+    }
+
+    fn run_command(
+        &self,
+        program: &str,
+        args: &[String],
+        _timeout_ms: u64,
+    ) -> CommandCaptureResult {
+        self.commands
+            .lock()
+            .unwrap()
+            .push((program.to_string(), args.to_vec()));
+        CommandCaptureResult {
+            code: 0,
+            stdout: format!("ran {program}\n"),
+            stderr: String::new(),
+            timed_out: false,
+        }
+    }
+
+    fn probe_media(&self, _path: &Path) -> Option<CommandCaptureResult> {
+        self.probe
+            .lock()
+            .unwrap()
+            .clone()
+            .map(|(code, stdout)| CommandCaptureResult {
+                code,
+                stdout,
+                stderr: String::new(),
+                timed_out: false,
+            })
+    }
+
+    fn fetch_formats(
+        &self,
+        _url: &str,
+        _extra_args: &[String],
+    ) -> Result<serde_json::Value, String> {
+        self.formats
+            .lock()
+            .unwrap()
+            .clone()
+            .ok_or_else(|| "no format list".to_string())
+    }
+}
+
+/// Helper: create a unique, empty directory for one test.
+fn unique_dir(label: &str) -> PathBuf {
+    let unique = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let dir = std::env::temp_dir().join(format!("worker_test_{unique}_{label}"));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Helper: a queued video job for the given URL.
+fn queued_job(id: &str, url: &str) -> QueueItem {
+    serde_json::from_value(serde_json::json!({
+        "id": id,
+        "title": format!("Test {id}"),
+        "url": url,
+        "mode": "video",
+        "qualityId": "best",
+        "status": "queued",
+        "progressPercent": 0.0,
+        "retryCount": 0,
+    }))
+    .unwrap()
+}
+
+/// Shared state, runtime and host for one pool under test.
+struct PoolFixture {
+    shared: Arc<Mutex<AppState>>,
+    runtime: Arc<Mutex<RuntimeState>>,
+    host: Arc<TestHost>,
+    download_dir: PathBuf,
+}
+
+impl PoolFixture {
+    fn new(label: &str, jobs: Vec<QueueItem>, max_retries: i32) -> Self {
+        let root = unique_dir(label);
+        let download_dir = root.join("downloads");
+        let mut settings = default_settings();
+        settings.download_dir = download_dir.to_string_lossy().to_string();
+        settings.max_retries = max_retries;
+
+        Self {
+            shared: Arc::new(Mutex::new(AppState {
+                queue: jobs,
+                settings,
+                active_worker_count: 0,
+                queue_pause_reason: None,
+            })),
+            runtime: Arc::new(Mutex::new(RuntimeState::default())),
+            host: Arc::new(TestHost {
+                temp_root: root.join("temp"),
+                emitted: Mutex::new(Vec::new()),
+                free_space: Mutex::new(None),
+                commands: Mutex::new(Vec::new()),
+                probe: Mutex::new(None),
+                formats: Mutex::new(None),
+            }),
+            download_dir,
+        }
+    }
+
+    fn start(&self, backend: Arc<ScriptedBackend>, size: usize) -> WorkerPool {
+        let backend: Arc<dyn DownloadBackend> = backend;
+        WorkerPool::start(
+            self.host.clone(),
+            backend,
+            self.shared.clone(),
+            self.runtime.clone(),
+            size,
+        )
+    }
+
+    /// Polls the shared state until `condition` holds, failing with `what` after 30 s.
+    fn wait_until(&self, what: &str, condition: impl Fn(&AppState) -> bool) {
+        let deadline = Instant::now() + Duration::from_secs(30);
+        while !condition(&self.shared.lock().unwrap()) {
+            assert!(Instant::now() < deadline, "{what}");
+            std::thread::sleep(Duration::from_millis(20));
+        }
+    }
+
+    /// Waits until no job is queued or downloading, then returns the queue.
+    fn wait_until_idle(&self) -> Vec<QueueItem> {
+        self.wait_until("worker pool did not go idle", |state| {
+            !state
+                .queue
+                .iter()
+                .any(|item| item.status == "queued" || item.status == "downloading")
+        });
+        self.shared.lock().unwrap().queue.clone()
+    }
+}
+
+/// Runs a pool of one worker over `jobs` until idle, then shuts it down.
+fn run_scripted(
+    label: &str,
+    jobs: Vec<QueueItem>,
+    backend: Arc<ScriptedBackend>,
+    max_retries: i32,
+) -> (Vec<QueueItem>, PathBuf) {
+    let fixture = PoolFixture::new(label, jobs, max_retries);
+    let pool = fixture.start(backend, 1);
+    let queue = fixture.wait_until_idle();
+    pool.shutdown().unwrap().join().unwrap();
+
+    let state = fixture.shared.lock().unwrap();
+    assert_eq!(
+        state.active_worker_count, 0,
+        "pool must release its workers"
+    );
+    (queue, fixture.download_dir.clone())
+}
+
+// =============================================================================
+// Scenarios
+// =============================================================================
+
+/// A successful attempt moves the temp file into the download dir and completes the job.
+#[test]
+fn test_worker_success_moves_file_and_completes() {
+    let backend = Arc::new(ScriptedBackend::new(vec![ScriptedAttempt::success(
+        &["[download]  50.0% of 10.00MiB at 1.00MiB/s ETA 00:05"],
+        "media.mp4",
+    )]));
+    let (queue, download_dir) = run_scripted(
+        "success",
+        vec![queued_job("job-1", "https://youtu.be/abc")],
+        backend.clone(),
+        3,
+    );
+
+    let item = &queue[0];
+    assert_eq!(item.status, "completed");
+    assert_eq!(item.progress_percent, 100.0);
+    let expected = download_dir.join("Test job-1.mp4");
+    assert_eq!(
+        item.output_path.as_deref(),
+        Some(expected.to_string_lossy().as_ref())
+    );
+    assert!(expected.exists());
+
+    let requests = backend.requests();
+    assert_eq!(requests.len(), 1);
+    assert!(requests[0]
+        .args
+        .contains(&"https://youtu.be/abc".to_string()));
+}
+
+/// An auth-gated failure fails immediately with the cookie prompt and is not retried.
+#[test]
+fn test_worker_needs_auth_is_not_retried() {
+    let backend = Arc::new(ScriptedBackend::new(vec![ScriptedAttempt::failure(&[
+        "ERROR: [youtube] abc: Private video. Sign in if you've been granted access",
+    ])]));
+    let (queue, _) = run_scripted(
+        "needs_auth",
+        vec![queued_job("job-2", "https://youtu.be/abc")],
+        backend.clone(),
+        3,
+    );
+
+    assert_eq!(queue[0].status, "failed");
+    assert_eq!(queue[0].retry_count, 0);
+    assert!(queue[0].error_message.is_some());
+    assert_eq!(backend.requests().len(), 1);
+}
+
+/// A transient network failure is retried and the second attempt completes the job.
+#[test]
+fn test_worker_network_error_retries_then_succeeds() {
+    let backend = Arc::new(ScriptedBackend::new(vec![
+        ScriptedAttempt::failure(&["ERROR: Unable to download webpage: Connection refused"]),
+        ScriptedAttempt::success(&[], "media.mp4"),
+    ]));
+    let (queue, _) = run_scripted(
+        "retry",
+        vec![queued_job("job-3", "https://youtu.be/abc")],
+        backend.clone(),
+        3,
+    );
+
+    assert_eq!(queue[0].status, "completed");
+    assert_eq!(queue[0].retry_count, 1);
+    assert_eq!(backend.requests().len(), 2);
+}
+
+/// With retries disabled, a failing attempt leaves the job failed after one request.
+#[test]
+fn test_worker_respects_zero_max_retries() {
+    let backend = Arc::new(ScriptedBackend::new(vec![ScriptedAttempt::failure(&[
+        "ERROR: Unable to download webpage: Connection refused",
+    ])]));
+    let (queue, _) = run_scripted(
+        "no_retry",
+        vec![queued_job("job-4", "https://youtu.be/abc")],
+        backend.clone(),
+        0,
+    );
+
+    assert_eq!(queue[0].status, "failed");
+    assert_eq!(backend.requests().len(), 1);
+}
+
+/// Every queued job is dispatched, even when there are more jobs than workers.
+#[test]
+fn test_pool_drains_more_jobs_than_workers() {
+    let backend = Arc::new(ScriptedBackend::new(vec![
+        ScriptedAttempt::success(&[], "media.mp4"),
+        ScriptedAttempt::success(&[], "media.mp4"),
+        ScriptedAttempt::success(&[], "media.mp4"),
+    ]));
+    let fixture = PoolFixture::new(
+        "drain",
+        vec![
+            queued_job("job-5", "https://youtu.be/a"),
+            queued_job("job-6", "https://youtu.be/b"),
+            queued_job("job-7", "https://youtu.be/c"),
+        ],
+        0,
+    );
+    let pool = fixture.start(backend.clone(), 2);
+
+    let queue = fixture.wait_until_idle();
+    pool.shutdown().unwrap().join().unwrap();
+
+    assert!(queue.iter().all(|item| item.status == "completed"));
+    assert_eq!(backend.requests().len(), 3);
+}
+
+/// Jobs queued after a resize and a notify are picked up by the resized pool.
+#[test]
+fn test_pool_resize_and_notify_dispatches_new_jobs() {
+    let backend = Arc::new(ScriptedBackend::new(vec![
+        ScriptedAttempt::success(&[], "media.mp4"),
+        ScriptedAttempt::success(&[], "media.mp4"),
+    ]));
+    let fixture = PoolFixture::new("resize", Vec::new(), 0);
+    let pool = fixture.start(backend.clone(), 1);
+
+    pool.resize(3);
+    pool.resize(2);
+    {
+        let mut state = fixture.shared.lock().unwrap();
+        state.queue.push(queued_job("job-8", "https://youtu.be/a"));
+        state.queue.push(queued_job("job-9", "https://youtu.be/b"));
+    }
+    pool.notify();
+
+    let queue = fixture.wait_until_idle();
+    pool.shutdown().unwrap().join().unwrap();
+
+    assert!(queue.iter().all(|item| item.status == "completed"));
+    assert_eq!(backend.requests().len(), 2);
+}
+
+/// After shutdown no further jobs are claimed; they stay queued for the next launch.
+#[test]
+fn test_pool_shutdown_stops_dispatching() {
+    let backend = Arc::new(ScriptedBackend::new(vec![ScriptedAttempt::success(
+        &[],
+        "media.mp4",
+    )]));
+    let fixture = PoolFixture::new("shutdown", Vec::new(), 0);
+    let pool = fixture.start(backend.clone(), 2);
+    pool.shutdown().unwrap().join().unwrap();
+
+    {
+        let mut state = fixture.shared.lock().unwrap();
+        state.queue.push(queued_job("job-10", "https://youtu.be/a"));
+    }
+
+    std::thread::sleep(Duration::from_millis(200));
+    let state = fixture.shared.lock().unwrap();
+    assert_eq!(state.queue[0].status, "queued");
+    assert!(backend.requests().is_empty());
+}
+
+// =============================================================================
+// Per-job update events
+// =============================================================================
+
+/// A burst of progress lines is coalesced into a few `job-updated` events, and
+/// the final event carries the terminal state plus any log lines held back.
+#[test]
+fn test_progress_burst_is_coalesced_into_job_updates() {
+    // Stays under the 120-line log cap so every delivered line is still in the log.
+    let lines: Vec<String> = (1..=100)
+        .map(|step| format!("[download] {step}.0% of 10.00MiB at 1.00MiB/s ETA 00:05"))
+        .collect();
+    let line_refs: Vec<&str> = lines.iter().map(String::as_str).collect();
+    let backend = Arc::new(ScriptedBackend::new(vec![ScriptedAttempt::success(
+        &line_refs,
+        "media.mp4",
+    )]));
+    let fixture = PoolFixture::new(
+        "coalesce",
+        vec![queued_job("job-11", "https://youtu.be/a")],
+        0,
+    );
+    let pool = fixture.start(backend, 1);
+    let queue = fixture.wait_until_idle();
+    pool.shutdown().unwrap().join().unwrap();
+
+    let updates = fixture.host.emitted.lock().unwrap();
+    assert!(updates.iter().all(|update| update.id == "job-11"));
+    assert!(
+        updates.len() < 20,
+        "expected coalesced updates, got {}",
+        updates.len()
+    );
+    let last = updates.last().unwrap();
+    assert_eq!(last.status, "completed");
+    assert_eq!(last.progress_percent, 100.0);
+
+    let delivered: usize = updates.iter().map(|update| update.log_lines.len()).sum();
+    assert_eq!(delivered, queue[0].download_log.as_ref().unwrap().len());
+}
+
+// =============================================================================
+// Disk space preflight
+// =============================================================================
+
+/// Low free space pauses the queue before yt-dlp starts; resuming dispatches again.
+#[test]
+fn test_low_disk_space_pauses_queue_before_download() {
+    let backend = Arc::new(ScriptedBackend::new(vec![ScriptedAttempt::success(
+        &[],
+        "media.mp4",
+    )]));
+    let mut job = queued_job("job-12", "https://youtu.be/a");
+    job.estimated_bytes = Some(50 * 1024 * 1024);
+    let fixture = PoolFixture::new("low_space", vec![job], 0);
+    *fixture.host.free_space.lock().unwrap() = Some(10 * 1024 * 1024);
+    let pool = fixture.start(backend.clone(), 1);
+
+    fixture.wait_until("queue was not paused", |state| {
+        state.queue_pause_reason.is_some() && state.queue[0].error_message.is_some()
+    });
+    {
+        let state = fixture.shared.lock().unwrap();
+        assert_eq!(state.queue[0].status, "queued");
+        assert!(state.queue[0].error_message.is_some());
+    }
+    assert!(backend.requests().is_empty(), "yt-dlp must not start");
+
+    *fixture.host.free_space.lock().unwrap() = Some(u64::MAX);
+    fixture.shared.lock().unwrap().queue_pause_reason = None;
+    pool.notify();
+
+    let queue = fixture.wait_until_idle();
+    pool.shutdown().unwrap().join().unwrap();
+    assert_eq!(queue[0].status, "completed");
+    assert_eq!(backend.requests().len(), 1);
+}
+
+// =============================================================================
+// Post-download hooks
+// =============================================================================
+
+/// Hooks matching the outcome run with expanded variables and log their output.
+#[test]
+fn test_post_download_hooks_run_for_matching_outcome() {
+    let backend = Arc::new(ScriptedBackend::new(vec![ScriptedAttempt::success(
+        &[],
+        "media.mp4",
+    )]));
+    let mut job = queued_job("job-13", "https://youtu.be/a");
+    job.channel = Some("Channel A".to_string());
+    let fixture = PoolFixture::new("hooks", vec![job], 0);
+    fixture.shared.lock().unwrap().settings.post_download_hooks = vec![
+        PostDownloadHook {
+            command: "transcribe".to_string(),
+            args: vec![
+                "{output_path}".to_string(),
+                "--channel={channel}".to_string(),
+            ],
+            run_on: HookTrigger::Completed,
+            timeout_sec: 5,
+        },
+        PostDownloadHook {
+            command: "notify-failure".to_string(),
+            args: vec!["{url}".to_string()],
+            run_on: HookTrigger::Failed,
+            timeout_sec: 5,
+        },
+    ];
+    let pool = fixture.start(backend, 1);
+
+    fixture.wait_until("hook did not run", |state| {
+        state.queue[0]
+            .download_log
+            .as_ref()
+            .is_some_and(|log| log.iter().any(|line| line == "[hook] ran transcribe"))
+    });
+    let queue = fixture.wait_until_idle();
+    pool.shutdown().unwrap().join().unwrap();
+
+    let commands = fixture.host.commands.lock().unwrap();
+    assert_eq!(commands.len(), 1, "only the completed hook runs");
+    let (program, args) = &commands[0];
+    assert_eq!(program, "transcribe");
+    assert_eq!(args[0], queue[0].output_path.clone().unwrap());
+    assert_eq!(args[1], "--channel=Channel A");
+
+    let log = queue[0].download_log.as_ref().unwrap();
+    assert!(log.iter().any(|line| line == "[hook] transcribe: exit 0"));
+    assert!(log.iter().any(|line| line == "[hook] ran transcribe"));
+}
+
+// =============================================================================
+// Integrity verification
+// =============================================================================
+
+/// A merge much shorter than the analyzed duration fails the job but keeps the file.
+#[test]
+fn test_truncated_output_fails_integrity_check() {
+    let backend = Arc::new(ScriptedBackend::new(vec![ScriptedAttempt::success(
+        &[],
+        "media.mp4",
+    )]));
+    let mut job = queued_job("job-14", "https://youtu.be/a");
+    job.duration_sec = Some(300.0);
+    let fixture = PoolFixture::new("truncated", vec![job], 3);
+    *fixture.host.probe.lock().unwrap() = Some((
+        0,
+        r#"{"streams":[{"codec_type":"video"},{"codec_type":"audio"}],"format":{"duration":"61.2"}}"#
+            .to_string(),
+    ));
+    let pool = fixture.start(backend.clone(), 1);
+    let queue = fixture.wait_until_idle();
+    pool.shutdown().unwrap().join().unwrap();
+
+    let item = &queue[0];
+    assert_eq!(item.status, "failed");
+    assert_eq!(item.integrity.as_deref(), Some("broken"));
+    assert!(item.error_message.as_ref().unwrap().contains("잘린"));
+    let output = fixture.download_dir.join("Test job-14.mp4");
+    assert!(output.exists());
+    assert!(item
+        .download_log
+        .as_ref()
+        .unwrap()
+        .iter()
+        .any(|line| line.starts_with("[verify] broken")));
+    assert_eq!(backend.requests().len(), 1, "broken output is not retried");
+}
+
+/// A matching probe marks the completed job verified.
+#[test]
+fn test_matching_output_is_verified() {
+    let backend = Arc::new(ScriptedBackend::new(vec![ScriptedAttempt::success(
+        &[],
+        "media.mp4",
+    )]));
+    let mut job = queued_job("job-15", "https://youtu.be/a");
+    job.duration_sec = Some(300.0);
+    let fixture = PoolFixture::new("verified", vec![job], 3);
+    *fixture.host.probe.lock().unwrap() = Some((
+        0,
+        r#"{"streams":[{"codec_type":"video"},{"codec_type":"audio"}],"format":{"duration":"300.4"}}"#
+            .to_string(),
+    ));
+    let pool = fixture.start(backend, 1);
+    let queue = fixture.wait_until_idle();
+    pool.shutdown().unwrap().join().unwrap();
+
+    assert_eq!(queue[0].status, "completed");
+    assert_eq!(queue[0].integrity.as_deref(), Some("verified"));
+    assert!(queue[0].error_message.is_none());
+}
+
+// =============================================================================
+// Retry policy and error categories
+// =============================================================================
+
+/// A full disk mid-download pauses the queue instead of burning retries.
+#[test]
+fn test_disk_full_error_pauses_queue() {
+    let backend = Arc::new(ScriptedBackend::new(vec![ScriptedAttempt::failure(&[
+        "ERROR: unable to write data: [Errno 28] No space left on device",
+    ])]));
+    let fixture = PoolFixture::new(
+        "disk_full",
+        vec![queued_job("job-16", "https://youtu.be/a")],
+        3,
+    );
+    let pool = fixture.start(backend.clone(), 1);
+
+    fixture.wait_until("queue was not paused", |state| {
+        state.queue_pause_reason.is_some()
+            && state.queue[0].status == "queued"
+            && state.queue[0].error_category == Some(RetryStrategy::DiskFull)
+    });
+    pool.shutdown().unwrap().join().unwrap();
+
+    let state = fixture.shared.lock().unwrap();
+    assert_eq!(state.queue[0].status, "queued");
+    assert_eq!(state.queue[0].error_category, Some(RetryStrategy::DiskFull));
+    assert_eq!(
+        backend.requests().len(),
+        1,
+        "no retry while the disk is full"
+    );
+}
+
+/// A per-category override allows retries for a category that normally fails at once.
+#[test]
+fn test_retry_policy_override_retries_category() {
+    let backend = Arc::new(ScriptedBackend::new(vec![
+        ScriptedAttempt::failure(&["ERROR: [youtube] a: Sign in to confirm you're not a bot"]),
+        ScriptedAttempt::success(&[], "media.mp4"),
+    ]));
+    let fixture = PoolFixture::new(
+        "bot_check_retry",
+        vec![queued_job("job-17", "https://youtu.be/a")],
+        3,
+    );
+    {
+        let mut state = fixture.shared.lock().unwrap();
+        state.settings.retry_policy.jitter_percent = 0;
+        state.settings.retry_policy.rules.insert(
+            RetryStrategy::BotCheck,
+            RetryRule {
+                max_retries: Some(1),
+                delays_ms: vec![10],
+            },
+        );
+    }
+    let pool = fixture.start(backend.clone(), 1);
+    let queue = fixture.wait_until_idle();
+    pool.shutdown().unwrap().join().unwrap();
+
+    assert_eq!(queue[0].status, "completed");
+    assert_eq!(queue[0].retry_count, 1);
+    assert_eq!(queue[0].error_category, None);
+    assert_eq!(backend.requests().len(), 2);
+}
+
+/// The first retry waits the first configured delay, not the second.
+#[test]
+fn test_first_retry_uses_first_configured_delay() {
+    let backend = Arc::new(ScriptedBackend::new(vec![
+        ScriptedAttempt::failure(&["ERROR: [youtube] a: Sign in to confirm you're not a bot"]),
+        ScriptedAttempt::success(&[], "media.mp4"),
+    ]));
+    let fixture = PoolFixture::new(
+        "first_retry_delay",
+        vec![queued_job("job-28", "https://youtu.be/a")],
+        3,
+    );
+    {
+        let mut state = fixture.shared.lock().unwrap();
+        state.settings.retry_policy.jitter_percent = 0;
+        state.settings.retry_policy.rules.insert(
+            RetryStrategy::BotCheck,
+            RetryRule {
+                max_retries: Some(1),
+                delays_ms: vec![10, 120_000],
+            },
+        );
+    }
+    let started = Instant::now();
+    let pool = fixture.start(backend.clone(), 1);
+    let queue = fixture.wait_until_idle();
+    pool.shutdown().unwrap().join().unwrap();
+
+    assert_eq!(queue[0].status, "completed");
+    assert_eq!(backend.requests().len(), 2);
+    assert!(
+        started.elapsed() < Duration::from_secs(10),
+        "retry waited the second delay"
+    );
+}
+
+// =============================================================================
+// Format fallback
+// =============================================================================
+
+/// A vanished format is replaced from a fresh format list and the job retried once.
+#[test]
+fn test_unavailable_format_falls_back_to_equivalent() {
+    let backend = Arc::new(ScriptedBackend::new(vec![
+        ScriptedAttempt::failure(&[
+            "ERROR: [youtube] a: Requested format is not available. Use --list-formats for a list of available formats",
+        ]),
+        ScriptedAttempt::success(&[], "media.mp4"),
+    ]));
+    let mut job = queued_job("job-18", "https://youtu.be/a");
+    job.quality_id = "137".to_string();
+    job.format_profile = Some(FormatProfile {
+        height: Some(1080),
+        codec: Some("avc1.640028".to_string()),
+        abr: None,
+    });
+    let fixture = PoolFixture::new("format_fallback", vec![job], 0);
+    *fixture.host.formats.lock().unwrap() = Some(serde_json::json!({
+        "formats": [
+            { "format_id": "136", "vcodec": "avc1.4d401f", "acodec": "none", "height": 720, "tbr": 1500.0 },
+            { "format_id": "248", "vcodec": "vp9", "acodec": "none", "height": 1080, "tbr": 2500.0 },
+            { "format_id": "140", "vcodec": "none", "acodec": "mp4a.40.2", "abr": 129.5 }
+        ]
+    }));
+    let pool = fixture.start(backend.clone(), 1);
+    let queue = fixture.wait_until_idle();
+    pool.shutdown().unwrap().join().unwrap();
+
+    assert_eq!(queue[0].status, "completed");
+    assert_eq!(queue[0].quality_id, "248");
+    assert_eq!(queue[0].retry_count, 0, "the substitution is not a retry");
+    let requests = backend.requests();
+    assert_eq!(requests.len(), 2);
+    assert!(requests[1]
+        .args
+        .iter()
+        .any(|arg| arg.starts_with("248+bestaudio")));
+    assert!(queue[0]
+        .download_log
+        .as_ref()
+        .unwrap()
+        .iter()
+        .any(|line| line == "[format] 137 is no longer available, using 248 (1080p vp9)"));
+}
+
+/// Without a usable format list the job fails as before.
+#[test]
+fn test_unavailable_format_fails_without_format_list() {
+    let backend = Arc::new(ScriptedBackend::new(vec![ScriptedAttempt::failure(&[
+        "ERROR: [youtube] a: Requested format is not available",
+    ])]));
+    let fixture = PoolFixture::new(
+        "format_fallback_none",
+        vec![queued_job("job-19", "https://youtu.be/a")],
+        3,
+    );
+    let pool = fixture.start(backend.clone(), 1);
+    let queue = fixture.wait_until_idle();
+    pool.shutdown().unwrap().join().unwrap();
+
+    assert_eq!(queue[0].status, "failed");
+    assert_eq!(
+        queue[0].error_category,
+        Some(RetryStrategy::FormatUnavailable)
+    );
+    assert_eq!(backend.requests().len(), 1);
+}
+
+// =============================================================================
+// Stall watchdog
+// =============================================================================
+
+/// A download that stops making progress is killed and retried as a network error.
+#[test]
+fn test_stalled_download_is_restarted() {
+    let backend = Arc::new(ScriptedBackend::new(vec![
+        ScriptedAttempt::hanging(&["[download]   5.0% of 10.00MiB"], "media.mp4.part"),
+        ScriptedAttempt::success(&[], "media.mp4"),
+    ]));
+    let fixture = PoolFixture::new(
+        "stall_watchdog",
+        vec![queued_job("job-22", "https://youtu.be/a")],
+        3,
+    );
+    {
+        let mut state = fixture.shared.lock().unwrap();
+        state.settings.stall_timeout_sec = 1;
+        state.settings.retry_policy.jitter_percent = 0;
+        state.settings.retry_policy.rules.insert(
+            RetryStrategy::NetworkError,
+            RetryRule {
+                max_retries: None,
+                delays_ms: vec![10],
+            },
+        );
+    }
+    let pool = fixture.start(backend.clone(), 1);
+    let queue = fixture.wait_until_idle();
+    pool.shutdown().unwrap().join().unwrap();
+
+    assert_eq!(queue[0].status, "completed");
+    assert_eq!(queue[0].retry_count, 1);
+    assert_eq!(backend.requests().len(), 2);
+    assert!(queue[0]
+        .download_log
+        .as_ref()
+        .unwrap()
+        .iter()
+        .any(|line| line == "[watchdog] stalled: no progress for 1s; restarting"));
+}
+
+// =============================================================================
+// Orderly shutdown
+// =============================================================================
+
+/// Shutdown stops the running download, requeues it with its partial data and
+/// leaves the remaining queued jobs untouched.
+#[test]
+fn test_shutdown_requeues_active_job_and_keeps_partials() {
+    let backend = Arc::new(ScriptedBackend::new(vec![ScriptedAttempt::hanging(
+        &["[download]  12.0% of 10.00MiB at 1.00MiB/s ETA 00:09"],
+        "media.mp4.part",
+    )]));
+    let fixture = PoolFixture::new(
+        "shutdown",
+        vec![
+            queued_job("job-20", "https://youtu.be/a"),
+            queued_job("job-21", "https://youtu.be/b"),
+        ],
+        3,
+    );
+    let pool = fixture.start(backend.clone(), 1);
+    fixture.runtime.lock().unwrap().pool = Some(pool);
+
+    fixture.wait_until("download did not start", |_| !backend.requests().is_empty());
+
+    let stopped = shutdown_worker_pool(&fixture.shared, &fixture.runtime, Duration::from_secs(10));
+    assert!(stopped, "workers must stop within the timeout");
+
+    let state = fixture.shared.lock().unwrap();
+    assert_eq!(state.queue[0].status, "queued");
+    assert!(state.queue[0]
+        .download_log
+        .as_ref()
+        .unwrap()
+        .iter()
+        .any(|line| line.starts_with("[shutdown] interrupted")));
+    assert_eq!(state.queue[1].status, "queued");
+    assert_eq!(backend.requests().len(), 1, "no job starts after shutdown");
+    assert!(fixture
+        .host
+        .temp_job_dir("job-20")
+        .join("media.mp4.part")
+        .exists());
+}
+
+/// Startup cleanup keeps temp dirs of resumable jobs and removes old orphans.
+#[test]
+fn test_clean_temp_job_dirs_keeps_resumable() {
+    let root = unique_dir("prune");
+    std::fs::create_dir_all(root.join("keep-me")).unwrap();
+    std::fs::write(root.join("keep-me").join("media.part"), b"x").unwrap();
+    std::fs::create_dir_all(root.join("orphan")).unwrap();
+    std::fs::write(root.join("orphan").join("media.part"), b"12345").unwrap();
+    std::fs::write(root.join("stray.tmp"), b"x").unwrap();
+
+    let keep: std::collections::HashSet<String> = ["keep-me".to_string()].into();
+    let report = clean_temp_job_dirs(&root, &keep, Duration::ZERO, std::time::SystemTime::now());
+
+    assert!(root.join("keep-me").join("media.part").exists());
+    assert!(!root.join("orphan").exists());
+    assert!(!root.join("stray.tmp").exists());
+    assert_eq!(report.removed_entries, 2);
+    assert_eq!(report.reclaimed_bytes, 6);
+}
+
+/// Orphans that changed recently may still belong to a job and are kept.
+#[test]
+fn test_clean_temp_job_dirs_keeps_recent_orphans() {
+    let root = unique_dir("prune_recent");
+    std::fs::create_dir_all(root.join("recent")).unwrap();
+    std::fs::write(root.join("recent").join("media.part"), b"x").unwrap();
+
+    let keep = std::collections::HashSet::new();
+    let max_age = Duration::from_secs(7 * 24 * 60 * 60);
+    let report = clean_temp_job_dirs(&root, &keep, max_age, std::time::SystemTime::now());
+    assert_eq!(report.removed_entries, 0);
+    assert!(root.join("recent").exists());
+
+    // A week later the same folder is an orphan.
+    let later = std::time::SystemTime::now() + max_age + Duration::from_secs(60);
+    let report = clean_temp_job_dirs(&root, &keep, max_age, later);
+    assert_eq!(report.removed_entries, 1);
+    assert!(!root.exists(), "an emptied temp root is removed");
+}
+
+/// Staging on the destination volume downloads into a hidden folder inside
+/// the download dir and removes the job folder afterwards.
+#[test]
+fn test_destination_volume_staging_uses_hidden_folder() {
+    let backend = Arc::new(ScriptedBackend::new(vec![ScriptedAttempt::success(
+        &[],
+        "media.mp4",
+    )]));
+    let fixture = PoolFixture::new(
+        "staging",
+        vec![queued_job("job-27", "https://youtu.be/s")],
+        0,
+    );
+    fixture.shared.lock().unwrap().settings.temp_location = TempLocation::DestinationVolume;
+    let pool = fixture.start(backend.clone(), 1);
+    let queue = fixture.wait_until_idle();
+    pool.shutdown().unwrap().join().unwrap();
+
+    assert_eq!(queue[0].status, "completed");
+    let staging = fixture.download_dir.join(STAGING_DIR_NAME);
+    assert_eq!(backend.requests()[0].temp_dir, staging.join("job-27"));
+    assert!(fixture.download_dir.join("Test job-27.mp4").exists());
+    assert!(!staging.join("job-27").exists());
+}
+
+// =============================================================================
+// Collision policy
+// =============================================================================
+
+/// Helper: a pool fixture whose output file for `job_id` already exists.
+fn fixture_with_existing_output(
+    label: &str,
+    job: QueueItem,
+    policy: CollisionPolicy,
+) -> (PoolFixture, PathBuf) {
+    let existing = PathBuf::from(format!("Test {}.mp4", job.id));
+    let fixture = PoolFixture::new(label, vec![job], 0);
+    fixture.shared.lock().unwrap().settings.collision_policy = policy;
+    let existing = fixture.download_dir.join(existing);
+    std::fs::create_dir_all(&fixture.download_dir).unwrap();
+    std::fs::write(&existing, b"existing").unwrap();
+    (fixture, existing)
+}
+
+/// A verified existing file completes the job without downloading again.
+#[test]
+fn test_skip_policy_completes_from_existing_file() {
+    let backend = Arc::new(ScriptedBackend::new(Vec::new()));
+    let mut job = queued_job("job-23", "https://youtu.be/a");
+    job.duration_sec = Some(300.0);
+    let (fixture, existing) =
+        fixture_with_existing_output("collision_skip", job, CollisionPolicy::Skip);
+    *fixture.host.probe.lock().unwrap() = Some((
+        0,
+        r#"{"streams":[{"codec_type":"video"},{"codec_type":"audio"}],"format":{"duration":"300.4"}}"#
+            .to_string(),
+    ));
+    let pool = fixture.start(backend.clone(), 1);
+    let queue = fixture.wait_until_idle();
+    pool.shutdown().unwrap().join().unwrap();
+
+    assert_eq!(queue[0].status, "completed");
+    assert_eq!(queue[0].integrity.as_deref(), Some("verified"));
+    assert_eq!(
+        queue[0].output_path.as_deref(),
+        Some(existing.to_string_lossy().as_ref())
+    );
+    assert!(backend.requests().is_empty());
+    assert!(queue[0]
+        .download_log
+        .as_ref()
+        .unwrap()
+        .iter()
+        .any(|line| line.starts_with("[skip]")));
+}
+
+/// A file much smaller than the analyzed size is replaced, not suffixed.
+#[test]
+fn test_skip_policy_replaces_partial_file() {
+    let backend = Arc::new(ScriptedBackend::new(vec![ScriptedAttempt::success(
+        &[],
+        "media.mp4",
+    )]));
+    let mut job = queued_job("job-24", "https://youtu.be/a");
+    job.estimated_bytes = Some(10_000);
+    let (fixture, existing) =
+        fixture_with_existing_output("collision_partial", job, CollisionPolicy::Skip);
+    let pool = fixture.start(backend.clone(), 1);
+    let queue = fixture.wait_until_idle();
+    pool.shutdown().unwrap().join().unwrap();
+
+    assert_eq!(queue[0].status, "completed");
+    assert_eq!(
+        queue[0].output_path.as_deref(),
+        Some(existing.to_string_lossy().as_ref())
+    );
+    assert_eq!(std::fs::read(&existing).unwrap(), b"media");
+    assert_eq!(backend.requests().len(), 1);
+}
+
+/// The fail policy fails the job up front and leaves the file alone.
+#[test]
+fn test_fail_policy_rejects_existing_file() {
+    let backend = Arc::new(ScriptedBackend::new(Vec::new()));
+    let (fixture, existing) = fixture_with_existing_output(
+        "collision_fail",
+        queued_job("job-25", "https://youtu.be/a"),
+        CollisionPolicy::Fail,
+    );
+    let pool = fixture.start(backend.clone(), 1);
+    let queue = fixture.wait_until_idle();
+    pool.shutdown().unwrap().join().unwrap();
+
+    assert_eq!(queue[0].status, "failed");
+    assert!(queue[0]
+        .error_message
+        .as_ref()
+        .unwrap()
+        .contains("이미 있습니다"));
+    assert!(backend.requests().is_empty());
+    assert_eq!(std::fs::read(&existing).unwrap(), b"existing");
+}
+
+// =============================================================================
+// Destination directories
+// =============================================================================
+
+/// A job with its own folder is written there, not to `download_dir`.
+#[test]
+fn test_job_output_dir_overrides_download_dir() {
+    let backend = Arc::new(ScriptedBackend::new(vec![ScriptedAttempt::success(
+        &[],
+        "media.mp4",
+    )]));
+    let custom_dir = unique_dir("custom_output");
+    let mut job = queued_job("job-26", "https://youtu.be/a");
+    job.output_dir = Some(custom_dir.to_string_lossy().to_string());
+    let fixture = PoolFixture::new("job_output_dir", vec![job], 0);
+    let pool = fixture.start(backend, 1);
+    let queue = fixture.wait_until_idle();
+    pool.shutdown().unwrap().join().unwrap();
+
+    let expected = custom_dir.join("Test job-26.mp4");
+    assert_eq!(queue[0].status, "completed");
+    assert_eq!(
+        queue[0].output_path.as_deref(),
+        Some(expected.to_string_lossy().as_ref())
+    );
+    assert!(expected.exists());
+    assert!(!fixture.download_dir.join("Test job-26.mp4").exists());
+}
+
+// =============================================================================
+// Per-job termination
+// =============================================================================
+
+/// Process stub that only records whether it was terminated.
+struct RecordingProcess(Arc<Mutex<bool>>);
+
+impl DownloadProcess for RecordingProcess {
+    fn take_output_streams(&mut self) -> Vec<Box<dyn std::io::Read + Send>> {
+        Vec::new()
+    }
+
+    fn try_wait(&mut self) -> std::io::Result<Option<bool>> {
+        Ok(None)
+    }
+
+    fn terminate(&mut self) {
+        *self.0.lock().unwrap() = true;
+    }
+}
+
+/// Pausing or canceling one job leaves the other workers' processes running.
+#[test]
+fn test_terminate_job_process_only_stops_target() {
+    let runtime = Arc::new(Mutex::new(RuntimeState::default()));
+    let mut terminated = Vec::new();
+    for job_id in ["job-a", "job-b"] {
+        let flag = Arc::new(Mutex::new(false));
+        let child: Box<dyn DownloadProcess> = Box::new(RecordingProcess(flag.clone()));
+        runtime.lock().unwrap().active_processes.insert(
+            job_id.to_string(),
+            ActiveProcess {
+                job_id: job_id.to_string(),
+                child: Arc::new(Mutex::new(child)),
+            },
+        );
+        terminated.push(flag);
+    }
+
+    terminate_job_process(&runtime, "job-a");
+
+    assert!(*terminated[0].lock().unwrap());
+    assert!(!*terminated[1].lock().unwrap());
+    let guard = runtime.lock().unwrap();
+    assert!(!guard.active_processes.contains_key("job-a"));
+    assert!(guard.active_processes.contains_key("job-b"));
+}
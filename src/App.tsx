@@ -6,12 +6,14 @@ import { QueuePage } from "./renderer/domains/queue/QueuePage";
 import { SettingsPage } from "./renderer/domains/settings/SettingsPage";
 import { DependencyBootstrapOverlay } from "./renderer/components/DependencyBootstrapOverlay";
 import { useDependencyBootstrap } from "./renderer/hooks/useDependencyBootstrap";
+import { useForwardedLaunch } from "./renderer/hooks/useForwardedLaunch";
 import { useQueueEvents } from "./renderer/hooks/useQueueEvents";
 import { useToastBridge } from "./renderer/hooks/useToastBridge";
 
 function App() {
   const dependencyBootstrapStatus = useDependencyBootstrap();
   useQueueEvents();
+  useForwardedLaunch();
   useToastBridge();
 
   return (
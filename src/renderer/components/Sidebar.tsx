@@ -4,6 +4,7 @@ import { Disc, Download, ListVideo, Settings } from "lucide-react";
 import { useMemo } from "react";
 import { useQueueStore } from "../store/queueStore";
 import { cn } from "../lib/cn";
+import { formatBytes } from "../lib/formatBytes";
 import { useStorageStats } from "../hooks/useStorageStats";
 
 export function Sidebar() {
@@ -83,16 +84,3 @@ export function Sidebar() {
     </aside>
   );
 }
-
-function formatBytes(value: number) {
-  if (!Number.isFinite(value) || value <= 0) return "0 B";
-  const units = ["B", "KB", "MB", "GB", "TB"];
-  let size = value;
-  let unitIndex = 0;
-  while (size >= 1024 && unitIndex < units.length - 1) {
-    size /= 1024;
-    unitIndex += 1;
-  }
-  const decimals = unitIndex >= 3 ? 1 : 0;
-  return `${size.toFixed(decimals)} ${units[unitIndex]}`;
-}
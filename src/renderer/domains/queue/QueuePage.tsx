@@ -18,6 +18,10 @@ export function QueuePage() {
   const { t } = useTranslation();
   const jobs = useQueueStore((state) => state.jobs);
   const applyQueueSnapshot = useQueueStore((state) => state.applyQueueSnapshot);
+  const pauseReason = useQueueStore((state) => state.pauseReason);
+  const applyPauseReason = useQueueStore((state) => state.applyPauseReason);
+  const migration = useQueueStore((state) => state.migration);
+  const applyMigrationProgress = useQueueStore((state) => state.applyMigrationProgress);
   const setToast = useUIStore((state) => state.setToast);
   const openVideoInBrowser = useOpenExternalUrl();
 
@@ -26,7 +30,7 @@ export function QueuePage() {
     [jobs],
   );
   const completedJobs = useMemo(
-    () => jobs.filter((job) => job.status === "completed"),
+    () => jobs.filter((job) => job.status === "completed" || job.status === "missing"),
     [jobs],
   );
   const totalProgress = useMemo(
@@ -78,6 +82,17 @@ export function QueuePage() {
     }
   };
 
+  const onResumeQueue = async () => {
+    try {
+      const items = await queueActions.resumeQueue();
+      applyQueueSnapshot(items);
+      applyPauseReason(undefined);
+    } catch (error) {
+      console.error(error);
+      setToast({ type: "error", message: t("common.unknownError") });
+    }
+  };
+
   const onCancelJob = async (jobId: string) => {
     try {
       const items = await queueActions.cancelJob(jobId);
@@ -98,8 +113,75 @@ export function QueuePage() {
     }
   };
 
+  const onRedownloadJob = async (jobId: string) => {
+    try {
+      const items = await queueActions.redownloadJob(jobId);
+      applyQueueSnapshot(items);
+    } catch (error) {
+      console.error(error);
+      setToast({ type: "error", message: t("common.unknownError") });
+    }
+  };
+
+  const onReconcileLibrary = async () => {
+    try {
+      const summary = await queueActions.reconcileLibrary();
+      setToast({
+        type: summary.missing > 0 ? "info" : "success",
+        message: t("queue.reconcileResult", {
+          checked: summary.checked,
+          relinked: summary.relinked,
+          missing: summary.missing,
+        }),
+      });
+    } catch (error) {
+      console.error(error);
+      setToast({ type: "error", message: t("common.unknownError") });
+    }
+  };
+
   return (
     <section className="max-w-6xl mx-auto pt-8 px-4">
+      {pauseReason ? (
+        <div className="mb-6 flex items-center justify-between gap-4 rounded-2xl border border-amber-500/40 bg-amber-500/10 px-4 py-3 text-sm text-amber-200">
+          <span>{pauseReason}</span>
+          <button
+            type="button"
+            className="shrink-0 font-semibold underline"
+            onClick={onResumeQueue}
+          >
+            {t("queue.resumeQueue")}
+          </button>
+        </div>
+      ) : null}
+
+      {migration ? (
+        <div className="mb-6 flex items-center justify-between gap-4 rounded-2xl border border-blue-500/40 bg-blue-500/10 px-4 py-3 text-sm text-blue-200">
+          <span className="min-w-0 truncate">
+            {migration.finished
+              ? t("queue.migrationFinished", {
+                  moved: migration.done - migration.conflicts.length - migration.failed.length,
+                  conflicts: migration.conflicts.length,
+                  failed: migration.failed.length,
+                })
+              : t("queue.migrationProgress", {
+                  done: migration.done,
+                  total: migration.total,
+                  percent: Math.round(migration.filePercent ?? 0),
+                })}
+          </span>
+          {migration.finished ? (
+            <button
+              type="button"
+              className="shrink-0 font-semibold underline"
+              onClick={() => applyMigrationProgress(undefined)}
+            >
+              {t("queue.dismiss")}
+            </button>
+          ) : null}
+        </div>
+      ) : null}
+
       <QueueSummaryCards
         activeCount={activeJobs.length}
         completedCount={completedJobs.length}
@@ -117,8 +199,10 @@ export function QueuePage() {
       <CompletedQueueList
         completedJobs={completedJobs}
         onClearTerminalJobs={onClearTerminalJobs}
+        onReconcileLibrary={onReconcileLibrary}
         onOpenFolder={queueActions.openFolder}
         onDeleteFile={onDeleteFile}
+        onRedownloadJob={onRedownloadJob}
       />
     </section>
   );
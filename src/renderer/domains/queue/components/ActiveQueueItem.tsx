@@ -65,9 +65,17 @@ export function ActiveQueueItem({
           <span className="capitalize text-zinc-500">
             {job.status} • {job.qualityId} • {job.mode}
           </span>
-          <span>
-            {Math.round(job.progressPercent)}% • ETA: {job.etaText ?? "-"}
-          </span>
+          {job.moveProgressPercent !== undefined ? (
+            <span>
+              {t("queue.movingFile", {
+                percent: Math.round(job.moveProgressPercent),
+              })}
+            </span>
+          ) : (
+            <span>
+              {Math.round(job.progressPercent)}% • ETA: {job.etaText ?? "-"}
+            </span>
+          )}
         </div>
         <div className="mt-2">
           <button
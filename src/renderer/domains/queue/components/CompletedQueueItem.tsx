@@ -1,37 +1,71 @@
-import { Check, FolderOpen, Trash2 } from "lucide-react";
+import { AlertTriangle, Check, FileX, FolderOpen, RotateCcw, Trash2 } from "lucide-react";
+import { useTranslation } from "react-i18next";
 import type { QueueItem } from "../../../types";
 
 interface CompletedQueueItemProps {
   job: QueueItem;
   onOpenFolder: (path: string) => Promise<void>;
   onDeleteFile: (path: string) => Promise<void>;
+  onRedownloadJob: (jobId: string) => Promise<void>;
 }
 
 export function CompletedQueueItem({
   job,
   onOpenFolder,
   onDeleteFile,
+  onRedownloadJob,
 }: CompletedQueueItemProps) {
+  const { t } = useTranslation();
+  const isMissing = job.status === "missing";
+
   return (
     <div className="flex items-center gap-4 p-4 hover:bg-zinc-800/30 transition-colors group">
       <div className="w-12 h-12 rounded bg-zinc-950 overflow-hidden shrink-0 relative">
         <div className="w-full h-full bg-zinc-800" />
         <div className="absolute inset-0 flex items-center justify-center">
-          <Check className="w-5 h-5 text-green-500 drop-shadow-md" />
+          {isMissing ? (
+            <FileX className="w-5 h-5 text-zinc-400 drop-shadow-md" />
+          ) : job.integrity === "suspect" ? (
+            <AlertTriangle className="w-5 h-5 text-amber-500 drop-shadow-md" />
+          ) : (
+            <Check className="w-5 h-5 text-green-500 drop-shadow-md" />
+          )}
         </div>
       </div>
       <div className="flex-1 min-w-0">
         <h4 className="text-sm font-medium text-white truncate">{job.title}</h4>
-        <p className="text-xs text-zinc-500 mt-0.5">{job.outputPath ?? "-"}</p>
+        <p
+          className={`text-xs mt-0.5 ${isMissing ? "text-zinc-600 line-through" : "text-zinc-500"}`}
+        >
+          {job.outputPath ?? "-"}
+        </p>
+        {isMissing && (
+          <p className="text-xs text-zinc-400 mt-0.5">{t("queue.fileMissing")}</p>
+        )}
+        {job.integrity === "suspect" && job.errorMessage && !isMissing && (
+          <p className="text-xs text-amber-500 mt-0.5">{job.errorMessage}</p>
+        )}
       </div>
-      <div className="flex items-center gap-2 opacity-0 group-hover:opacity-100 transition-opacity">
+      {isMissing && (
         <button
           type="button"
-          onClick={() => job.outputPath && void onOpenFolder(job.outputPath)}
-          className="p-2 rounded-lg hover:bg-zinc-800 text-zinc-400 hover:text-white transition-colors cursor-pointer"
+          onClick={() => void onRedownloadJob(job.id)}
+          className="inline-flex items-center gap-1 rounded-lg border border-zinc-700 px-3 py-1.5 text-xs text-zinc-300 hover:bg-zinc-800 transition-colors cursor-pointer"
         >
-          <FolderOpen className="w-4 h-4" />
+          <RotateCcw className="w-3.5 h-3.5" />
+          {t("queue.redownload")}
         </button>
+      )}
+      <div className="flex items-center gap-2 opacity-0 group-hover:opacity-100 transition-opacity">
+        {!isMissing && (
+          <button
+            type="button"
+            onClick={() => job.outputPath && void onOpenFolder(job.outputPath)}
+            className="p-2 rounded-lg hover:bg-zinc-800 text-zinc-400 hover:text-white transition-colors cursor-pointer"
+          >
+            <FolderOpen className="w-4 h-4" />
+          </button>
+        )}
         <button
           type="button"
           onClick={() => job.outputPath && void onDeleteFile(job.outputPath)}
@@ -6,15 +6,19 @@ import { CompletedQueueItem } from "./CompletedQueueItem";
 interface CompletedQueueListProps {
   completedJobs: QueueItem[];
   onClearTerminalJobs: () => Promise<void>;
+  onReconcileLibrary: () => Promise<void>;
   onOpenFolder: (path: string) => Promise<void>;
   onDeleteFile: (path: string) => Promise<void>;
+  onRedownloadJob: (jobId: string) => Promise<void>;
 }
 
 export function CompletedQueueList({
   completedJobs,
   onClearTerminalJobs,
+  onReconcileLibrary,
   onOpenFolder,
   onDeleteFile,
+  onRedownloadJob,
 }: CompletedQueueListProps) {
   const { t } = useTranslation();
 
@@ -25,14 +29,24 @@ export function CompletedQueueList({
           <CheckCircle2 className="w-5 h-5 text-green-500" />
           {t("queue.completedList")}
         </h2>
-        <button
-          type="button"
-          onClick={() => void onClearTerminalJobs()}
-          disabled={completedJobs.length === 0}
-          className="rounded-lg border border-zinc-700 px-3 py-1.5 text-xs text-zinc-300 hover:bg-zinc-800 disabled:opacity-50 disabled:cursor-not-allowed transition-colors cursor-pointer"
-        >
-          {t("queue.clearCompleted")}
-        </button>
+        <div className="flex items-center gap-2">
+          <button
+            type="button"
+            onClick={() => void onReconcileLibrary()}
+            disabled={completedJobs.length === 0}
+            className="rounded-lg border border-zinc-700 px-3 py-1.5 text-xs text-zinc-300 hover:bg-zinc-800 disabled:opacity-50 disabled:cursor-not-allowed transition-colors cursor-pointer"
+          >
+            {t("queue.reconcileLibrary")}
+          </button>
+          <button
+            type="button"
+            onClick={() => void onClearTerminalJobs()}
+            disabled={completedJobs.length === 0}
+            className="rounded-lg border border-zinc-700 px-3 py-1.5 text-xs text-zinc-300 hover:bg-zinc-800 disabled:opacity-50 disabled:cursor-not-allowed transition-colors cursor-pointer"
+          >
+            {t("queue.clearCompleted")}
+          </button>
+        </div>
       </div>
       <div className="bg-zinc-900 border border-zinc-800 rounded-2xl overflow-hidden">
         {completedJobs.length === 0 ? (
@@ -47,6 +61,7 @@ export function CompletedQueueList({
                 job={job}
                 onOpenFolder={onOpenFolder}
                 onDeleteFile={onDeleteFile}
+                onRedownloadJob={onRedownloadJob}
               />
             ))}
           </div>
@@ -8,6 +8,8 @@ const {
   openFolderMock,
   deleteFileAndGetSnapshotMock,
   clearTerminalJobsAndGetSnapshotMock,
+  redownloadJobAndGetSnapshotMock,
+  reconcileLibraryMock,
 } = vi.hoisted(() => ({
   pauseJobAndGetSnapshotMock: vi.fn(),
   resumeJobAndGetSnapshotMock: vi.fn(),
@@ -15,6 +17,8 @@ const {
   openFolderMock: vi.fn(),
   deleteFileAndGetSnapshotMock: vi.fn(),
   clearTerminalJobsAndGetSnapshotMock: vi.fn(),
+  redownloadJobAndGetSnapshotMock: vi.fn(),
+  reconcileLibraryMock: vi.fn(),
 }));
 
 vi.mock("../../lib/desktopClient", () => ({
@@ -24,6 +28,8 @@ vi.mock("../../lib/desktopClient", () => ({
   openFolder: openFolderMock,
   deleteFileAndGetSnapshot: deleteFileAndGetSnapshotMock,
   clearTerminalJobsAndGetSnapshot: clearTerminalJobsAndGetSnapshotMock,
+  redownloadJobAndGetSnapshot: redownloadJobAndGetSnapshotMock,
+  reconcileLibrary: reconcileLibraryMock,
 }));
 
 describe("queueActions", () => {
@@ -56,4 +62,15 @@ describe("queueActions", () => {
     expect(clearTerminalJobsAndGetSnapshotMock).toHaveBeenCalledTimes(1);
     expect(items).toEqual([{ id: "1", title: "job" }]);
   });
+
+  it("re-queues missing downloads and reconciles the library", async () => {
+    redownloadJobAndGetSnapshotMock.mockResolvedValue({ items: [] });
+    reconcileLibraryMock.mockResolvedValue({ checked: 2, relinked: 0, restored: 0, missing: 1 });
+
+    await queueActions.redownloadJob("d");
+    const summary = await queueActions.reconcileLibrary();
+
+    expect(redownloadJobAndGetSnapshotMock).toHaveBeenCalledWith("d");
+    expect(summary.missing).toBe(1);
+  });
 });
@@ -4,7 +4,10 @@ import {
   deleteFileAndGetSnapshot,
   openFolder,
   pauseJobAndGetSnapshot,
+  reconcileLibrary,
+  redownloadJobAndGetSnapshot,
   resumeJobAndGetSnapshot,
+  resumeQueueAndGetSnapshot,
 } from "../../lib/desktopClient";
 
 export async function clearCompletedQueueJobs() {
@@ -22,6 +25,11 @@ export async function resumeQueueJob(id: string) {
   return snapshot.items;
 }
 
+export async function resumeQueue() {
+  const snapshot = await resumeQueueAndGetSnapshot();
+  return snapshot.items;
+}
+
 export async function cancelQueueJob(id: string) {
   const snapshot = await cancelJobAndGetSnapshot(id);
   return snapshot.items;
@@ -32,11 +40,19 @@ export async function deleteQueueFile(path: string) {
   return snapshot.items;
 }
 
+export async function redownloadQueueJob(id: string) {
+  const snapshot = await redownloadJobAndGetSnapshot(id);
+  return snapshot.items;
+}
+
 export const queueActions = {
   pauseJob: pauseQueueJob,
   resumeJob: resumeQueueJob,
+  resumeQueue,
   cancelJob: cancelQueueJob,
   openFolder,
   deleteFile: deleteQueueFile,
+  redownloadJob: redownloadQueueJob,
+  reconcileLibrary,
   clearCompletedQueueJobs,
 };
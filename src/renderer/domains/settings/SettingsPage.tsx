@@ -5,12 +5,17 @@ import { FormProvider, useForm } from "react-hook-form";
 import { Save } from "lucide-react";
 import {
   checkUpdate,
+  cleanTempDownloads,
+  migrateDownloads,
   openExternalUrl,
   pickDownloadDir,
   runDiagnostics,
   setSettings,
 } from "../../lib/desktopClient";
+import { formatBytes } from "../../lib/formatBytes";
+import { openConfirmModal } from "../../lib/openConfirmModal";
 import { settingsQueries, settingsQueryOptions } from "../../queries";
+import { useQueueStore } from "../../store/queueStore";
 import { useUIStore } from "../../store/uiStore";
 import type { AppSettings } from "../../types";
 import { SettingsDefaultsSection } from "./components/SettingsDefaultsSection";
@@ -47,13 +52,39 @@ export function SettingsPage() {
     settingsForm.reset(settingsQuery.data);
   }, [settingsForm, settingsQuery.data]);
 
+  const offerDownloadMigration = async (previousDir: string) => {
+    const hasCompletedJobs = useQueueStore
+      .getState()
+      .jobs.some((job) => job.status === "completed" && job.outputPath?.startsWith(previousDir));
+    if (!hasCompletedJobs) return;
+
+    const confirmed = await openConfirmModal({
+      title: t("settings.migrateDownloads"),
+      description: t("settings.migrateDownloadsConfirm"),
+      confirmText: t("settings.migrateDownloads"),
+      cancelText: t("common.cancel"),
+    });
+    if (!confirmed) return;
+
+    try {
+      await migrateDownloads(previousDir);
+    } catch (error) {
+      console.error(error);
+      setToast({ type: "error", message: String(error) });
+    }
+  };
+
   const saveSettingsMutation = useMutation({
     mutationFn: setSettings,
     onSuccess: async (_response, nextSettings) => {
+      const previousDir = settingsQuery.data?.downloadDir;
       queryClient.setQueryData(settingsQueries.current.queryKey, nextSettings);
       settingsForm.reset(nextSettings);
       await i18n.changeLanguage(nextSettings.language);
       setToast({ type: "success", message: t("settings.saved") });
+      if (previousDir && previousDir !== nextSettings.downloadDir) {
+        await offerDownloadMigration(previousDir);
+      }
     },
     onError: (error) => {
       console.error(error);
@@ -72,6 +103,23 @@ export function SettingsPage() {
     },
   });
 
+  const cleanTempMutation = useMutation({
+    mutationFn: cleanTempDownloads,
+    onSuccess: (report) => {
+      setToast({
+        type: "success",
+        message: t("settings.cleanTempResult", {
+          count: report.removedEntries,
+          size: formatBytes(report.reclaimedBytes),
+        }),
+      });
+    },
+    onError: (error) => {
+      console.error(error);
+      setToast({ type: "error", message: t("common.unknownError") });
+    },
+  });
+
   const updateMutation = useMutation({
     mutationFn: checkUpdate,
     onSuccess: async (result) => {
@@ -134,6 +182,8 @@ export function SettingsPage() {
             diagnostics={diagnostics}
             isPending={diagnosticsMutation.isPending}
             onDiagnose={() => diagnosticsMutation.mutate()}
+            isCleaning={cleanTempMutation.isPending}
+            onCleanTemp={() => cleanTempMutation.mutate()}
           />
 
           <SettingsUpdateSection
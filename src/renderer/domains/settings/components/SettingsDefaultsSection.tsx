@@ -10,6 +10,12 @@ import {
 } from "../../../components/ui/select";
 import type { AppSettings } from "../../../types";
 
+const EMBED_OPTIONS = [
+  { name: "embedMetadata", labelKey: "settings.embedMetadata" },
+  { name: "embedThumbnail", labelKey: "settings.embedThumbnail" },
+  { name: "embedChapters", labelKey: "settings.embedChapters" },
+] as const;
+
 interface SettingsDefaultsSectionProps {
   isLoading: boolean;
   onPickDownloadDir: () => Promise<void>;
@@ -103,6 +109,27 @@ export function SettingsDefaultsSection({
             </button>
           </div>
         </div>
+        <div>
+          <span className="block text-sm font-medium text-zinc-400 mb-2">
+            {t("settings.embed")}
+          </span>
+          <div className="flex flex-wrap gap-6">
+            {EMBED_OPTIONS.map((option) => (
+              <label
+                key={option.name}
+                className="inline-flex items-center gap-2 text-sm text-zinc-300"
+              >
+                <input
+                  type="checkbox"
+                  className="w-4 h-4 accent-blue-500"
+                  disabled={isLoading}
+                  {...register(option.name)}
+                />
+                {t(option.labelKey)}
+              </label>
+            ))}
+          </div>
+        </div>
       </div>
     </div>
   );
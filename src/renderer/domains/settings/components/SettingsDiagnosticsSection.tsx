@@ -5,12 +5,16 @@ interface SettingsDiagnosticsSectionProps {
   diagnostics: string;
   isPending: boolean;
   onDiagnose: () => void;
+  isCleaning: boolean;
+  onCleanTemp: () => void;
 }
 
 export function SettingsDiagnosticsSection({
   diagnostics,
   isPending,
   onDiagnose,
+  isCleaning,
+  onCleanTemp,
 }: SettingsDiagnosticsSectionProps) {
   const { t } = useTranslation();
 
@@ -32,6 +36,14 @@ export function SettingsDiagnosticsSection({
         >
           {t("settings.runDiagnostics")}
         </button>
+        <button
+          type="button"
+          onClick={onCleanTemp}
+          disabled={isCleaning}
+          className="ml-2 bg-zinc-800 hover:bg-zinc-700 text-white px-4 py-2 rounded-xl font-medium transition-colors disabled:opacity-50 disabled:cursor-not-allowed"
+        >
+          {t("settings.cleanTemp")}
+        </button>
       </div>
     </div>
   );
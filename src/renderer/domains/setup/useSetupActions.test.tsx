@@ -36,6 +36,8 @@ const mockAnalysisResult: AnalysisResult = {
   channel: "Channel",
   durationSec: 120,
   thumbnailUrl: "https://example.com/thumb.jpg",
+  videoId: "abc1234",
+  uploadDate: "20240131",
   videoOptions: [{ id: "137+140", label: "1080p", ext: "mp4", type: "video" }],
   audioOptions: [{ id: "140", label: "128kbps", ext: "m4a", type: "audio" }],
 };
@@ -102,6 +104,10 @@ describe("useSetupActions", () => {
       mode: "video",
       qualityId: "137+140",
       forceDuplicate: false,
+      channel: "Channel",
+      durationSec: 120,
+      videoId: "abc1234",
+      uploadDate: "20240131",
     });
   });
 });
@@ -120,6 +120,10 @@ export function useSetupActions(onSuccessEnqueue: () => void) {
           }
         }
 
+        const selectedOption = (
+          selectedMode === "video" ? analysisResult.videoOptions : analysisResult.audioOptions
+        ).find((option) => option.id === selectedQualityId);
+
         await enqueueJob({
           url: urlInput.trim(),
           title: analysisResult.title,
@@ -127,6 +131,12 @@ export function useSetupActions(onSuccessEnqueue: () => void) {
           mode: selectedMode,
           qualityId: selectedQualityId,
           forceDuplicate: shouldForceDuplicate,
+          estimatedBytes: selectedOption?.estimatedBytes,
+          channel: analysisResult.channel,
+          durationSec: analysisResult.durationSec,
+          formatProfile: selectedOption?.profile,
+          videoId: analysisResult.videoId,
+          uploadDate: analysisResult.uploadDate,
         });
         setToast({ type: "success", message: t("setup.toast.addedToQueue") });
         onSuccessEnqueue();
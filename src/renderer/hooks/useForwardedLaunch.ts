@@ -0,0 +1,24 @@
+import { useEffect } from "react";
+import { useNavigate } from "react-router-dom";
+import { onForwardedLaunch } from "../lib/desktopClient";
+import { useSetupStore } from "../store/setupStore";
+
+/** Opens a URL passed to a second launch of the app in the setup page. */
+export function useForwardedLaunch() {
+  const navigate = useNavigate();
+  const setUrlInput = useSetupStore((state) => state.setUrlInput);
+
+  useEffect(() => {
+    const unlisten = onForwardedLaunch(({ urls }) => {
+      const [url] = urls;
+      if (!url) return;
+      setUrlInput(url);
+      navigate("/setup");
+    });
+    return () => {
+      if (unlisten) {
+        unlisten();
+      }
+    };
+  }, [navigate, setUrlInput]);
+}
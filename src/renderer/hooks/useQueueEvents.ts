@@ -1,14 +1,25 @@
 import { useEffect } from "react";
-import { getQueueSnapshot, isNativeDesktop, onQueueUpdated } from "../lib/desktopClient";
+import {
+  getQueueSnapshot,
+  isNativeDesktop,
+  onDownloadMigrationProgress,
+  onJobUpdated,
+  onQueueUpdated,
+} from "../lib/desktopClient";
 import { useQueueStore } from "../store/queueStore";
 
 const WEB_QUEUE_POLLING_INTERVAL_MS = 300;
 
 export function useQueueEvents() {
   const applyQueueSnapshot = useQueueStore((state) => state.applyQueueSnapshot);
+  const applyJobUpdate = useQueueStore((state) => state.applyJobUpdate);
+  const applyPauseReason = useQueueStore((state) => state.applyPauseReason);
+  const applyMigrationProgress = useQueueStore((state) => state.applyMigrationProgress);
 
   useEffect(() => {
     let unlisten: (() => void) | undefined;
+    let unlistenJob: (() => void) | undefined;
+    let unlistenMigration: (() => void) | undefined;
     let pollTimer: number | undefined;
     const nativeDesktop = isNativeDesktop();
 
@@ -16,11 +27,15 @@ export function useQueueEvents() {
       if (nativeDesktop) {
         unlisten = onQueueUpdated((snapshot) => {
           applyQueueSnapshot(snapshot.items);
+          applyPauseReason(snapshot.pauseReason);
         });
+        unlistenJob = onJobUpdated(applyJobUpdate);
+        unlistenMigration = onDownloadMigrationProgress(applyMigrationProgress);
 
         // Initial hydration once, then rely on event stream only.
         const snapshot = await getQueueSnapshot();
         applyQueueSnapshot(snapshot.items);
+        applyPauseReason(snapshot.pauseReason);
         return;
       }
 
@@ -39,9 +54,15 @@ export function useQueueEvents() {
       if (unlisten) {
         unlisten();
       }
+      if (unlistenJob) {
+        unlistenJob();
+      }
+      if (unlistenMigration) {
+        unlistenMigration();
+      }
       if (pollTimer) {
         clearInterval(pollTimer);
       }
     };
-  }, [applyQueueSnapshot]);
+  }, [applyQueueSnapshot, applyJobUpdate, applyPauseReason, applyMigrationProgress]);
 }
@@ -2,12 +2,20 @@ import type {
   AnalysisResult,
   AppSettings,
   DependencyBootstrapStatus,
+  ForwardedArgs,
   DiagnosticsResult,
   DownloadMode,
   DuplicateCheckResult,
+  FormatProfile,
+  JobUpdate,
+  MigrationProgress,
+  ProxyTestResult,
   QueueItem,
   QueueSnapshot,
+  ReconcileSummary,
+  SponsorBlockMode,
   StorageStats,
+  TempCleanupReport,
 } from "../types";
 
 export function isNativeDesktop() {
@@ -39,9 +47,14 @@ export const DESKTOP_CHANNEL = {
   CHECK_DUPLICATE: "check_duplicate",
   PAUSE_JOB: "pause_job",
   RESUME_JOB: "resume_job",
+  REDOWNLOAD_JOB: "redownload_job",
+  RESUME_QUEUE: "resume_queue",
   CANCEL_JOB: "cancel_job",
   CLEAR_TERMINAL_JOBS: "clear_terminal_jobs",
   DELETE_FILE: "delete_file",
+  RECONCILE_LIBRARY: "reconcile_library",
+  MIGRATE_DOWNLOADS: "migrate_downloads",
+  CLEAN_TEMP_DOWNLOADS: "clean_temp_downloads",
   OPEN_FOLDER: "open_folder",
   OPEN_EXTERNAL_URL: "open_external_url",
   GET_QUEUE_SNAPSHOT: "get_queue_snapshot",
@@ -51,6 +64,7 @@ export const DESKTOP_CHANNEL = {
   RUN_DIAGNOSTICS: "run_diagnostics",
   CHECK_UPDATE: "check_update",
   GET_STORAGE_STATS: "get_storage_stats",
+  TEST_PROXY: "test_proxy",
   GET_DEPENDENCY_BOOTSTRAP_STATUS: "get_dependency_bootstrap_status",
 } as const;
 
@@ -110,7 +124,10 @@ function subscribeToTauriEvent<TPayload>(
   };
 }
 
-export async function analyzeUrl(url: string): Promise<AnalysisResult> {
+export async function analyzeUrl(
+  url: string,
+  cookies?: { cookiesFile?: string; cookiesBrowser?: string },
+): Promise<AnalysisResult> {
   if (shouldUseMockMode()) {
     return {
       sourceUrl: url,
@@ -125,7 +142,7 @@ export async function analyzeUrl(url: string): Promise<AnalysisResult> {
       audioOptions: [{ id: "140", label: "128kbps", ext: "m4a", type: "audio" }],
     };
   }
-  return invokeCommand(DESKTOP_CHANNEL.ANALYZE_URL, { url });
+  return invokeCommand(DESKTOP_CHANNEL.ANALYZE_URL, { url, ...cookies });
 }
 
 export async function enqueueJob(input: {
@@ -135,6 +152,21 @@ export async function enqueueJob(input: {
   mode: DownloadMode;
   qualityId: string;
   forceDuplicate: boolean;
+  embedMetadata?: boolean;
+  embedThumbnail?: boolean;
+  embedChapters?: boolean;
+  sponsorblockMode?: SponsorBlockMode;
+  cookiesFile?: string;
+  cookiesBrowser?: string;
+  estimatedBytes?: number;
+  channel?: string;
+  durationSec?: number;
+  formatProfile?: FormatProfile;
+  videoId?: string;
+  uploadDate?: string;
+  playlist?: string;
+  playlistIndex?: number;
+  outputDir?: string;
 }): Promise<{ jobId: string }> {
   if (shouldUseMockMode()) {
     const id = crypto.randomUUID();
@@ -194,7 +226,7 @@ export async function clearTerminalJobs(): Promise<void> {
   if (shouldUseMockMode()) {
     for (let index = mockQueue.length - 1; index >= 0; index -= 1) {
       const status = mockQueue[index].status;
-      if (status === "completed") {
+      if (status === "completed" || status === "missing") {
         mockQueue.splice(index, 1);
       }
     }
@@ -231,6 +263,10 @@ export async function resumeJobAndGetSnapshot(id: string) {
   return invokeQueueMutation(DESKTOP_CHANNEL.RESUME_JOB, { id });
 }
 
+export async function resumeQueueAndGetSnapshot() {
+  return invokeQueueMutation(DESKTOP_CHANNEL.RESUME_QUEUE);
+}
+
 export async function cancelJobAndGetSnapshot(id: string) {
   return invokeQueueMutation(DESKTOP_CHANNEL.CANCEL_JOB, { id });
 }
@@ -243,6 +279,28 @@ export async function deleteFileAndGetSnapshot(path: string) {
   return invokeQueueMutation(DESKTOP_CHANNEL.DELETE_FILE, { path });
 }
 
+export async function redownloadJobAndGetSnapshot(id: string) {
+  return invokeQueueMutation(DESKTOP_CHANNEL.REDOWNLOAD_JOB, { id });
+}
+
+export async function reconcileLibrary(): Promise<ReconcileSummary> {
+  if (shouldUseMockMode()) {
+    const checked = mockQueue.filter((item) => item.status === "completed").length;
+    return { checked, relinked: 0, restored: 0, missing: 0 };
+  }
+  return invokeCommand(DESKTOP_CHANNEL.RECONCILE_LIBRARY);
+}
+
+export async function migrateDownloads(fromDir: string): Promise<void> {
+  if (shouldUseMockMode()) return;
+  await invokeCommand<void>(DESKTOP_CHANNEL.MIGRATE_DOWNLOADS, { fromDir });
+}
+
+export async function cleanTempDownloads(): Promise<TempCleanupReport> {
+  if (shouldUseMockMode()) return { removedEntries: 0, reclaimedBytes: 0 };
+  return invokeCommand(DESKTOP_CHANNEL.CLEAN_TEMP_DOWNLOADS);
+}
+
 export async function openFolder(path: string): Promise<void> {
   if (shouldUseMockMode()) return;
   await invokeCommand(DESKTOP_CHANNEL.OPEN_FOLDER, { path });
@@ -313,6 +371,13 @@ export async function getStorageStats(): Promise<StorageStats> {
   return invokeCommand(DESKTOP_CHANNEL.GET_STORAGE_STATS);
 }
 
+export async function testProxy(proxyUrl?: string): Promise<ProxyTestResult> {
+  if (shouldUseMockMode()) {
+    return { ok: true, statusCode: 204, elapsedMs: 42, message: "mock" };
+  }
+  return invokeCommand(DESKTOP_CHANNEL.TEST_PROXY, { proxyUrl });
+}
+
 export async function getDependencyBootstrapStatus(): Promise<DependencyBootstrapStatus> {
   if (shouldUseMockMode()) {
     return {
@@ -330,6 +395,25 @@ export function onQueueUpdated(listener: (snapshot: QueueSnapshot) => void): (()
   return subscribeToTauriEvent<QueueSnapshot>("queue-updated", listener);
 }
 
+export function onJobUpdated(listener: (update: JobUpdate) => void): (() => void) | undefined {
+  if (shouldUseMockMode()) return undefined;
+  return subscribeToTauriEvent<JobUpdate>("job-updated", listener);
+}
+
+export function onDownloadMigrationProgress(
+  listener: (progress: MigrationProgress) => void,
+): (() => void) | undefined {
+  if (shouldUseMockMode()) return undefined;
+  return subscribeToTauriEvent<MigrationProgress>("download-migration-progress", listener);
+}
+
+export function onForwardedLaunch(
+  listener: (forwarded: ForwardedArgs) => void,
+): (() => void) | undefined {
+  if (shouldUseMockMode()) return undefined;
+  return subscribeToTauriEvent<ForwardedArgs>("instance-args-forwarded", listener);
+}
+
 export function onDependencyBootstrapUpdated(
   listener: (status: DependencyBootstrapStatus) => void,
 ): (() => void) | undefined {
@@ -0,0 +1,12 @@
+export function formatBytes(value: number) {
+  if (!Number.isFinite(value) || value <= 0) return "0 B";
+  const units = ["B", "KB", "MB", "GB", "TB"];
+  let size = value;
+  let unitIndex = 0;
+  while (size >= 1024 && unitIndex < units.length - 1) {
+    size /= 1024;
+    unitIndex += 1;
+  }
+  const decimals = unitIndex >= 3 ? 1 : 0;
+  return `${size.toFixed(decimals)} ${units[unitIndex]}`;
+}
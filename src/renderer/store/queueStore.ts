@@ -1,12 +1,47 @@
 import { create } from "zustand";
-import type { QueueItem } from "../types";
+import type { JobUpdate, MigrationProgress, QueueItem } from "../types";
+
+// Mirrors MAX_LOG_LINES_PER_JOB in src-tauri/src/queue.rs.
+const MAX_LOG_LINES_PER_JOB = 120;
 
 interface QueueStore {
   jobs: QueueItem[];
+  pauseReason?: string;
+  migration?: MigrationProgress;
   applyQueueSnapshot: (jobs: QueueItem[]) => void;
+  applyPauseReason: (pauseReason?: string) => void;
+  applyJobUpdate: (update: JobUpdate) => void;
+  applyMigrationProgress: (migration?: MigrationProgress) => void;
+}
+
+function mergeJobUpdate(job: QueueItem, update: JobUpdate): QueueItem {
+  const downloadLog = update.logLines?.length
+    ? [...(job.downloadLog ?? []), ...update.logLines].slice(-MAX_LOG_LINES_PER_JOB)
+    : job.downloadLog;
+  return {
+    ...job,
+    status: update.status,
+    progressPercent: update.progressPercent,
+    speedText: update.speedText,
+    etaText: update.etaText,
+    outputPath: update.outputPath,
+    errorMessage: update.errorMessage,
+    errorCategory: update.errorCategory,
+    retryCount: update.retryCount,
+    sponsorblockSegments: update.sponsorblockSegments,
+    integrity: update.integrity,
+    moveProgressPercent: update.moveProgressPercent,
+    downloadLog,
+  };
 }
 
 export const useQueueStore = create<QueueStore>((set) => ({
   jobs: [],
   applyQueueSnapshot: (jobs) => set({ jobs }),
+  applyPauseReason: (pauseReason) => set({ pauseReason }),
+  applyMigrationProgress: (migration) => set({ migration }),
+  applyJobUpdate: (update) =>
+    set((state) => ({
+      jobs: state.jobs.map((job) => (job.id === update.id ? mergeJobUpdate(job, update) : job)),
+    })),
 }));
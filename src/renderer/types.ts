@@ -6,14 +6,17 @@ export const JOB_STATUSES = [
   "downloading",
   "paused",
   "completed",
+  "missing",
   "failed",
   "canceled",
 ] as const;
 export type JobStatus = (typeof JOB_STATUSES)[number];
 
-export const TERMINAL_JOB_STATUSES = ["completed", "failed", "canceled"] as const;
+export const TERMINAL_JOB_STATUSES = ["completed", "missing", "failed", "canceled"] as const;
 export type TerminalJobStatus = (typeof TERMINAL_JOB_STATUSES)[number];
 
+export type SponsorBlockMode = "off" | "remove" | "mark";
+
 export const SUPPORTED_LANGUAGES = ["ko", "en"] as const;
 export type AppLanguage = (typeof SUPPORTED_LANGUAGES)[number];
 
@@ -22,6 +25,14 @@ export interface QualityOption {
   label: string;
   ext: string;
   type: DownloadMode;
+  estimatedBytes?: number;
+  profile?: FormatProfile;
+}
+
+export interface FormatProfile {
+  height?: number;
+  codec?: string;
+  abr?: number;
 }
 
 export interface AnalysisResult {
@@ -32,6 +43,8 @@ export interface AnalysisResult {
   thumbnailUrl: string;
   videoOptions: QualityOption[];
   audioOptions: QualityOption[];
+  videoId?: string;
+  uploadDate?: string;
 }
 
 export interface QueueItem {
@@ -49,10 +62,78 @@ export interface QueueItem {
   errorMessage?: string;
   retryCount: number;
   downloadLog?: string[];
+  embedMetadata?: boolean;
+  embedThumbnail?: boolean;
+  embedChapters?: boolean;
+  sponsorblockMode?: SponsorBlockMode;
+  sponsorblockSegments?: number;
+  cookiesFile?: string;
+  cookiesBrowser?: string;
+  estimatedBytes?: number;
+  channel?: string;
+  durationSec?: number;
+  integrity?: IntegrityStatus;
+  errorCategory?: ErrorCategory;
+  formatProfile?: FormatProfile;
+  videoId?: string;
+  uploadDate?: string;
+  playlist?: string;
+  playlistIndex?: number;
+  outputDir?: string;
+  moveProgressPercent?: number;
+  outputBytes?: number;
+}
+
+export type ErrorCategory =
+  | "noRetry"
+  | "needsAuth"
+  | "botCheck"
+  | "formatUnavailable"
+  | "geoBlocked"
+  | "diskFull"
+  | "ffmpegError"
+  | "rateLimit"
+  | "networkError"
+  | "default";
+
+export type IntegrityStatus = "verified" | "suspect" | "broken";
+
+export interface ReconcileSummary {
+  checked: number;
+  relinked: number;
+  restored: number;
+  missing: number;
+}
+
+export interface MigrationProgress {
+  total: number;
+  done: number;
+  currentFile?: string;
+  filePercent?: number;
+  conflicts: string[];
+  failed: string[];
+  finished: boolean;
 }
 
 export interface QueueSnapshot {
   items: QueueItem[];
+  pauseReason?: string;
+}
+
+export interface JobUpdate {
+  id: string;
+  status: JobStatus;
+  progressPercent: number;
+  speedText?: string;
+  etaText?: string;
+  outputPath?: string;
+  errorMessage?: string;
+  errorCategory?: ErrorCategory;
+  retryCount: number;
+  sponsorblockSegments?: number;
+  integrity?: IntegrityStatus;
+  moveProgressPercent?: number;
+  logLines?: string[];
 }
 
 export interface DiagnosticsResult {
@@ -66,6 +147,69 @@ export interface AppSettings {
   downloadDir: string;
   maxRetries: number;
   language: AppLanguage;
+  embedMetadata?: boolean;
+  embedThumbnail?: boolean;
+  embedChapters?: boolean;
+  sponsorblockMode?: SponsorBlockMode;
+  sponsorblockCategories?: string[];
+  cookiesFile?: string | null;
+  cookiesBrowser?: string | null;
+  proxyUrl?: string | null;
+  minFreeSpaceMb?: number;
+  postDownloadHooks?: PostDownloadHook[];
+  retryPolicy?: RetryPolicy;
+  stallTimeoutSec?: number;
+  fileNameTemplate?: string;
+  collisionPolicy?: CollisionPolicy;
+  audioDownloadDir?: string | null;
+  videoDownloadDir?: string | null;
+  targetFilesystem?: TargetFilesystem;
+  verifyMoveChecksum?: boolean;
+  reconcileOnStartup?: boolean;
+  tempLocation?: TempLocation;
+  tempOrphanMaxAgeDays?: number;
+}
+
+export type TempLocation = "appData" | "destinationVolume";
+
+export interface ForwardedArgs {
+  args: string[];
+  urls: string[];
+}
+
+export interface TempCleanupReport {
+  removedEntries: number;
+  reclaimedBytes: number;
+}
+
+export type TargetFilesystem = "portable" | "ntfs" | "ext4" | "apfs";
+
+export type CollisionPolicy = "autoSuffix" | "overwrite" | "skip" | "fail";
+
+export interface RetryRule {
+  maxRetries?: number;
+  delaysMs?: number[];
+}
+
+export interface RetryPolicy {
+  jitterPercent?: number;
+  rules?: Partial<Record<ErrorCategory, RetryRule>>;
+}
+
+export type HookTrigger = "completed" | "failed" | "always";
+
+export interface PostDownloadHook {
+  command: string;
+  args?: string[];
+  runOn?: HookTrigger;
+  timeoutSec?: number;
+}
+
+export interface ProxyTestResult {
+  ok: boolean;
+  statusCode?: number;
+  elapsedMs: number;
+  message: string;
 }
 
 export interface DuplicateCheckResult {